@@ -10,39 +10,148 @@ use anchor_lang::{
         system_instruction,
         sysvar::clock::Clock,
     },
-    Result,
+    system_program, Result,
 };
 use anchor_spl::{
-    associated_token,
+    associated_token::{self, AssociatedToken},
     token::{
         self,
-        spl_token::{self, instruction::AuthorityType, state::Mint},
-        InitializeAccount, Mint as MintAccount, MintTo, SetAuthority, Token, TokenAccount,
-        Transfer,
+        spl_token::{
+            self,
+            instruction::AuthorityType,
+            state::{Account as TokenAccountStruct, Mint},
+        },
+        Burn, CloseAccount, InitializeAccount, Mint as MintAccount, MintTo, SetAuthority, Token,
+        TokenAccount, Transfer,
     },
 };
 
 use borsh::{BorshDeserialize, BorshSerialize};
 
+use crate::access::events::{
+    Claimed, MintFinalized, ProjectClaimed, ProjectTokensClaimed, Refunded, UnclaimedSwept,
+};
+use crate::access::owner::revert_donation;
 use crate::access::types::{
-    AccessControl, DonateTracker, OwnerAccount, CONSTRAINT_SEED, DONATE_SEED, PLATFORM_SEED,
+    AccessControl, DonateTracker, OwnerAccount, SweepPolicy, CONSTRAINT_SEED, DONATE_SEED,
+    PLATFORM_SEED,
 };
+use crate::access::vesting::vested_amount;
+
+#[derive(Accounts)]
+pub struct FinalizeMint<'info> {
+    #[account(has_one = access_control_token_account, has_one = token)]
+    pub access_control: Account<'info, AccessControl>,
+    #[account(mut)]
+    pub token: Account<'info, MintAccount>,
+    #[account(mut)]
+    pub access_control_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+/// Mints the donator and project supply after launch and drops the mint
+/// authority, claims are only possible afterwards. The freeze authority is
+/// already revoked by the launch.
+pub fn finalize_mint(ctx: Context<FinalizeMint>) -> Result<()> {
+    require!(
+        ctx.accounts.access_control.donate_amount >= ctx.accounts.access_control.donate_amount_min,
+        ClaimError::DonationAmountLessThanMinimumDonationAmount
+    );
+
+    require!(
+        ctx.accounts.access_control.pool.is_some(),
+        ClaimError::PoolNotInitialized
+    );
+
+    require!(
+        ctx.accounts.token.mint_authority.is_some(),
+        ClaimError::MintFinalized
+    );
+
+    require!(
+        ctx.accounts.access_control_token_account.owner == ctx.accounts.access_control.key(),
+        ClaimError::AccountError
+    );
+
+    require!(
+        ctx.accounts.access_control_token_account.mint == ctx.accounts.access_control.token,
+        ClaimError::AccountError
+    );
+
+    let bump = ctx.accounts.access_control.bump;
+    let seeds = &[
+        CONSTRAINT_SEED,
+        ctx.accounts.access_control.project_wallet.as_ref(),
+        &[bump],
+    ];
+    let signer_seeds = &[&seeds[..]][..];
+
+    let amount =
+        ctx.accounts.access_control.minted - ctx.accounts.access_control.token_amount_for_pool;
+
+    let mint_ix = MintTo {
+        mint: ctx.accounts.token.to_account_info(),
+        to: ctx.accounts.access_control_token_account.to_account_info(),
+        authority: ctx.accounts.access_control.to_account_info(),
+    };
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        mint_ix,
+        signer_seeds,
+    );
+
+    token::mint_to(cpi_ctx, amount)?;
+
+    let cpi_accounts = SetAuthority {
+        current_authority: ctx.accounts.access_control.to_account_info(),
+        account_or_mint: ctx.accounts.token.to_account_info(),
+    };
+    let cpi_context = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+
+    token::set_authority(cpi_context, AuthorityType::MintTokens, None)?;
+
+    ctx.accounts.token.reload()?;
+
+    require!(
+        ctx.accounts.token.supply == ctx.accounts.access_control.minted,
+        ClaimError::SupplyMismatch
+    );
+
+    emit!(MintFinalized {
+        access_control: ctx.accounts.access_control.key(),
+        token: ctx.accounts.token.key(),
+        amount,
+        supply: ctx.accounts.token.supply,
+    });
+
+    Ok(())
+}
 
 #[derive(Accounts)]
 pub struct Claim<'info> {
     #[account(has_one = access_control_token_account, has_one = token)]
     pub access_control: Account<'info, AccessControl>,
-    #[account(mut, has_one = donator, has_one = access_control)]
+    #[account(mut, has_one = donator, has_one = access_control, constraint = donate_tracker.is_authorized(&authority.key()) @ ClaimError::Unauthorized)]
     pub donate_tracker: Account<'info, DonateTracker>,
-    pub donator: Signer<'info>,
+    /// CHECK: SAFE. Owner of the donate tracker, receives the tokens
+    pub donator: AccountInfo<'info>,
+    /// The donator or its registered delegate
     #[account(mut)]
+    pub authority: Signer<'info>,
     pub token: Account<'info, MintAccount>,
     /// CHECK: SAFE
     #[account(mut)]
     pub access_control_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
+    #[account(init_if_needed, payer = authority, associated_token::mint = token, associated_token::authority = donator)]
     pub to: Account<'info, TokenAccount>,
     pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
 }
 
 pub fn claim(ctx: Context<Claim>) -> Result<()> {
@@ -52,7 +161,7 @@ pub fn claim(ctx: Context<Claim>) -> Result<()> {
     );
 
     require!(
-        ctx.accounts.access_control.pool != None,
+        ctx.accounts.access_control.pool.is_some(),
         ClaimError::PoolNotInitialized
     );
 
@@ -76,49 +185,30 @@ pub fn claim(ctx: Context<Claim>) -> Result<()> {
         ClaimError::AccountError
     );
 
-    if ctx.accounts.token.mint_authority.is_some() {
-        let mint_ix = MintTo {
-            mint: ctx.accounts.token.to_account_info(),
-            to: ctx.accounts.access_control_token_account.to_account_info(),
-            authority: ctx.accounts.access_control.to_account_info(),
-        };
+    let current_time = Clock::get()?.unix_timestamp as u32;
+    let access_control = &ctx.accounts.access_control;
 
-        let bump = ctx.accounts.access_control.bump;
-        let seeds = &[
-            CONSTRAINT_SEED,
-            ctx.accounts.access_control.project_wallet.as_ref(),
-            &[bump],
-        ];
-        let signer_seeds = &[&seeds[..]][..];
+    require!(
+        !access_control.claim_expired(current_time),
+        ClaimError::ClaimExpired
+    );
 
-        let cpi_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            mint_ix,
-            signer_seeds,
-        );
+    let vested = vested_amount(
+        ctx.accounts.donate_tracker.token_amount,
+        access_control.vesting_tge_ratio,
+        access_control.launched_at,
+        access_control.vesting_cliff,
+        access_control.vesting_duration,
+        current_time,
+    );
+    let amount = vested - ctx.accounts.donate_tracker.claimed_amount;
 
-        token::mint_to(
-            cpi_ctx,
-            ctx.accounts.access_control.minted - ctx.accounts.access_control.token_amount_for_pool,
-        )?;
+    require!(amount > 0, ClaimError::NothingToClaim);
 
-        let cpi_accounts = SetAuthority {
-            current_authority: ctx.accounts.access_control.to_account_info(),
-            account_or_mint: ctx.accounts.token.to_account_info(),
-        };
-        let cpi_program = ctx.accounts.token_program.to_account_info();
-        let bump = ctx.accounts.access_control.bump;
-        let seeds = &[
-            CONSTRAINT_SEED,
-            ctx.accounts.access_control.project_wallet.as_ref(),
-            &[bump],
-        ];
-        let signer_seeds = &[&seeds[..]][..];
-        let cpi_context = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
-
-        token::set_authority(cpi_context, AuthorityType::MintTokens, None)?;
-    } else {
-    }
+    require!(
+        ctx.accounts.token.mint_authority.is_none(),
+        ClaimError::MintNotFinalized
+    );
 
     let transfer_ix = Transfer {
         from: ctx.accounts.access_control_token_account.to_account_info(),
@@ -140,127 +230,320 @@ pub fn claim(ctx: Context<Claim>) -> Result<()> {
         signer_seeds,
     );
 
-    token::transfer(cpi_ctx, ctx.accounts.donate_tracker.token_amount)?;
+    token::transfer(cpi_ctx, amount)?;
 
     let donate_tracker = &mut ctx.accounts.donate_tracker;
-    donate_tracker.token_amount = 0;
+    donate_tracker.claimed_amount += amount;
+
+    emit!(Claimed {
+        access_control: ctx.accounts.access_control.key(),
+        donate_tracker: ctx.accounts.donate_tracker.key(),
+        donator: ctx.accounts.donate_tracker.donator,
+        to: ctx.accounts.to.key(),
+        amount,
+        claimed_amount: ctx.accounts.donate_tracker.claimed_amount,
+    });
 
     Ok(())
 }
 
 #[derive(Accounts)]
-pub struct ClaimForProject<'info> {
-    #[account(mut, has_one = project_wallet, has_one = wsol_account, has_one = access_control_token_account, has_one = token)]
+pub struct Distribute<'info> {
+    #[account(has_one = access_control_token_account, has_one = token)]
     pub access_control: Account<'info, AccessControl>,
-    pub project_wallet: Signer<'info>,
-    #[account(mut)]
     pub token: Account<'info, MintAccount>,
-    /// CHECK: SAFE
-    pub wsol: AccountInfo<'info>,
     #[account(mut)]
     pub access_control_token_account: Account<'info, TokenAccount>,
-    /// CHECK: SAFE
-    #[account(mut)]
-    pub wsol_account: Account<'info, TokenAccount>,
-    /// CHECK: SAFE
-    #[account(has_one = platform_wsol)]
-    pub platform: Account<'info, OwnerAccount>,
-    /// CHECK: SAFE
-    #[account(mut)]
-    pub platform_wsol: Account<'info, TokenAccount>,
-    /// CHECK: SAFE
-    #[account(mut)]
-    pub token_to: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub wsol_to: Account<'info, TokenAccount>,
     pub token_program: Program<'info, Token>,
 }
 
-pub fn claim_for_project(ctx: Context<ClaimForProject>) -> Result<()> {
+/// Pushes vested tokens to donators so they need not claim themselves, anyone may call it.
+/// `remaining_accounts` holds (donate tracker, donator token ATA) pairs.
+pub fn distribute<'info>(ctx: Context<'_, '_, 'info, 'info, Distribute<'info>>) -> Result<()> {
     require!(
         ctx.accounts.access_control.donate_amount >= ctx.accounts.access_control.donate_amount_min,
         ClaimError::DonationAmountLessThanMinimumDonationAmount
     );
 
     require!(
-        ctx.accounts.access_control.pool != None,
+        ctx.accounts.access_control.pool.is_some(),
         ClaimError::PoolNotInitialized
     );
 
     require!(
-        ctx.accounts.wsol.to_account_info().key == &spl_token::native_mint::id(),
-        ClaimError::WSOLAccountIsNotNativeMint
+        ctx.accounts.access_control_token_account.owner == ctx.accounts.access_control.key(),
+        ClaimError::AccountError
     );
 
     require!(
-        ctx.accounts.access_control_token_account.owner == ctx.accounts.access_control.key(),
+        ctx.accounts.access_control_token_account.mint == ctx.accounts.access_control.token,
         ClaimError::AccountError
     );
 
     require!(
-        ctx.accounts
-            .access_control_token_account
-            .to_account_info()
-            .owner
-            .clone()
-            == token::ID,
+        ctx.remaining_accounts
+            .chunks_exact(2)
+            .remainder()
+            .is_empty(),
         ClaimError::AccountError
     );
 
     require!(
-        ctx.accounts.access_control_token_account.mint == ctx.accounts.access_control.token,
-        ClaimError::AccountError
+        ctx.accounts.token.mint_authority.is_none(),
+        ClaimError::MintNotFinalized
     );
 
-    if ctx.accounts.token.mint_authority.is_some() {
-        let mint_ix = MintTo {
-            mint: ctx.accounts.token.to_account_info(),
-            to: ctx.accounts.access_control_token_account.to_account_info(),
-            authority: ctx.accounts.access_control.to_account_info(),
-        };
+    let bump = ctx.accounts.access_control.bump;
+    let seeds = &[
+        CONSTRAINT_SEED,
+        ctx.accounts.access_control.project_wallet.as_ref(),
+        &[bump],
+    ];
+    let signer_seeds = &[&seeds[..]][..];
+
+    let current_time = Clock::get()?.unix_timestamp as u32;
+    let access_control = &ctx.accounts.access_control;
+
+    require!(
+        !access_control.claim_expired(current_time),
+        ClaimError::ClaimExpired
+    );
+
+    for pair in ctx.remaining_accounts.chunks_exact(2) {
+        let mut donate_tracker = Account::<DonateTracker>::try_from(&pair[0])?;
+        let to = &pair[1];
+
+        require!(
+            donate_tracker.access_control == access_control.key(),
+            ClaimError::AccountError
+        );
 
-        let bump = ctx.accounts.access_control.bump;
-        let seeds = &[
-            CONSTRAINT_SEED,
-            ctx.accounts.access_control.project_wallet.as_ref(),
-            &[bump],
-        ];
-        let signer_seeds = &[&seeds[..]][..];
+        require!(
+            to.key()
+                == associated_token::get_associated_token_address(
+                    &donate_tracker.donator,
+                    &access_control.token
+                ),
+            ClaimError::AccountError
+        );
+
+        let vested = vested_amount(
+            donate_tracker.token_amount,
+            access_control.vesting_tge_ratio,
+            access_control.launched_at,
+            access_control.vesting_cliff,
+            access_control.vesting_duration,
+            current_time,
+        );
+        let amount = vested - donate_tracker.claimed_amount;
+
+        // already claimed up to the vested amount, e.g. through `claim`
+        if amount == 0 {
+            continue;
+        }
+
+        // the donator has not created the ATA yet, leave the tracker for a later batch
+        if !is_token_account(to) {
+            continue;
+        }
+
+        let transfer_ix = Transfer {
+            from: ctx.accounts.access_control_token_account.to_account_info(),
+            to: to.clone(),
+            authority: access_control.to_account_info(),
+        };
 
         let cpi_ctx = CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            mint_ix,
+            transfer_ix,
             signer_seeds,
         );
 
-        token::mint_to(
-            cpi_ctx,
-            ctx.accounts.access_control.minted - ctx.accounts.access_control.token_amount_for_pool,
-        )?;
+        token::transfer(cpi_ctx, amount)?;
+
+        donate_tracker.claimed_amount += amount;
+        donate_tracker.exit(ctx.program_id)?;
+
+        emit!(Claimed {
+            access_control: access_control.key(),
+            donate_tracker: donate_tracker.key(),
+            donator: donate_tracker.donator,
+            to: to.key(),
+            amount,
+            claimed_amount: donate_tracker.claimed_amount,
+        });
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SweepUnclaimed<'info> {
+    #[account(has_one = access_control_token_account, has_one = token)]
+    pub access_control: Account<'info, AccessControl>,
+    #[account(mut)]
+    pub token: Account<'info, MintAccount>,
+    #[account(mut)]
+    pub access_control_token_account: Account<'info, TokenAccount>,
+    #[account(seeds = [PLATFORM_SEED], bump)]
+    pub platform: Account<'info, OwnerAccount>,
+    /// CHECK: SAFE. Project or platform token account, checked against the sweep policy, unused when burning
+    #[account(mut)]
+    pub destination: AccountInfo<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+/// Permissionless once the claim deadline passed. `remaining_accounts` holds
+/// donate trackers, the unclaimed part of each is marked expired and swept
+/// according to the project's sweep policy.
+pub fn sweep_unclaimed<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SweepUnclaimed<'info>>,
+) -> Result<()> {
+    let current_time = Clock::get()?.unix_timestamp as u32;
+    let access_control = &ctx.accounts.access_control;
+
+    require!(
+        access_control.claim_expired(current_time),
+        ClaimError::ClaimNotExpired
+    );
+
+    require!(
+        ctx.accounts.token.mint_authority.is_none(),
+        ClaimError::MintNotFinalized
+    );
+
+    match access_control.sweep_policy {
+        SweepPolicy::Project | SweepPolicy::Platform => {
+            let destination = TokenAccountStruct::unpack(&ctx.accounts.destination.data.borrow())?;
+            let destination_owner = if access_control.sweep_policy == SweepPolicy::Project {
+                access_control.project_wallet
+            } else {
+                ctx.accounts.platform.owner
+            };
+
+            require!(
+                ctx.accounts.destination.owner == &token::ID
+                    && destination.owner == destination_owner
+                    && destination.mint == access_control.token,
+                ClaimError::AccountError
+            );
+        }
+        SweepPolicy::Burn => {}
+    }
+
+    let mut amount = 0;
+
+    for info in ctx.remaining_accounts.iter() {
+        let mut donate_tracker = Account::<DonateTracker>::try_from(info)?;
+
+        require!(
+            donate_tracker.access_control == access_control.key(),
+            ClaimError::AccountError
+        );
+
+        if donate_tracker.expired {
+            continue;
+        }
+
+        amount += donate_tracker.token_amount - donate_tracker.claimed_amount;
+        donate_tracker.expired = true;
+        donate_tracker.exit(ctx.program_id)?;
+    }
+
+    require!(amount > 0, ClaimError::NothingToClaim);
+
+    let bump = access_control.bump;
+    let seeds = &[
+        CONSTRAINT_SEED,
+        access_control.project_wallet.as_ref(),
+        &[bump],
+    ];
+    let signer_seeds = &[&seeds[..]][..];
 
-        let cpi_accounts = SetAuthority {
-            current_authority: ctx.accounts.access_control.to_account_info(),
-            account_or_mint: ctx.accounts.token.to_account_info(),
+    if access_control.sweep_policy == SweepPolicy::Burn {
+        let burn_ix = Burn {
+            mint: ctx.accounts.token.to_account_info(),
+            from: ctx.accounts.access_control_token_account.to_account_info(),
+            authority: access_control.to_account_info(),
         };
-        let cpi_program = ctx.accounts.token_program.to_account_info();
-        let bump = ctx.accounts.access_control.bump;
-        let seeds = &[
-            CONSTRAINT_SEED,
-            ctx.accounts.project_wallet.key.as_ref(),
-            &[bump],
-        ];
-        let signer_seeds = &[&seeds[..]][..];
-        let cpi_context = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
-
-        token::set_authority(cpi_context, AuthorityType::MintTokens, None)?;
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            burn_ix,
+            signer_seeds,
+        );
+
+        token::burn(cpi_ctx, amount)?;
     } else {
+        let transfer_ix = Transfer {
+            from: ctx.accounts.access_control_token_account.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: access_control.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_ix,
+            signer_seeds,
+        );
+
+        token::transfer(cpi_ctx, amount)?;
     }
 
-    let transfer_ix = Transfer {
-        from: ctx.accounts.access_control_token_account.to_account_info(),
-        to: ctx.accounts.token_to.to_account_info(),
-        authority: ctx.accounts.access_control.to_account_info(),
-    };
+    emit!(UnclaimedSwept {
+        access_control: access_control.key(),
+        sweep_policy: access_control.sweep_policy,
+        destination: ctx.accounts.destination.key(),
+        amount,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimForProject<'info> {
+    #[account(mut, has_one = project_wallet, has_one = wsol_account)]
+    pub access_control: Account<'info, AccessControl>,
+    pub project_wallet: Signer<'info>,
+    /// CHECK: SAFE
+    pub wsol: AccountInfo<'info>,
+    /// CHECK: SAFE
+    #[account(mut)]
+    pub wsol_account: Account<'info, TokenAccount>,
+    /// CHECK: SAFE
+    #[account(has_one = platform_wsol)]
+    pub platform: Account<'info, OwnerAccount>,
+    /// CHECK: SAFE
+    #[account(mut)]
+    pub platform_wsol: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub wsol_to: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn claim_for_project(ctx: Context<ClaimForProject>) -> Result<()> {
+    require!(
+        ctx.accounts.access_control.donate_amount >= ctx.accounts.access_control.donate_amount_min,
+        ClaimError::DonationAmountLessThanMinimumDonationAmount
+    );
+
+    require!(
+        ctx.accounts.access_control.pool.is_some(),
+        ClaimError::PoolNotInitialized
+    );
+
+    require!(
+        ctx.accounts.wsol.to_account_info().key == &spl_token::native_mint::id(),
+        ClaimError::WSOLAccountIsNotNativeMint
+    );
+
+    let access_control = &ctx.accounts.access_control;
+    let released = (access_control.sol_amount_for_project as u128
+        * access_control.released_ratio() as u128
+        / 100) as u64;
+    let amount = released - access_control.project_sol_claimed;
+
+    require!(amount > 0, ClaimError::NothingToClaim);
 
     let bump = ctx.accounts.access_control.bump;
     let seeds = &[
@@ -270,19 +553,23 @@ pub fn claim_for_project(ctx: Context<ClaimForProject>) -> Result<()> {
     ];
     let signer_seeds = &[&seeds[..]][..];
 
+    let transfer_ix = Transfer {
+        from: ctx.accounts.wsol_account.to_account_info(),
+        to: ctx.accounts.wsol_to.to_account_info(),
+        authority: ctx.accounts.access_control.to_account_info(),
+    };
+
     let cpi_ctx = CpiContext::new_with_signer(
         ctx.accounts.token_program.to_account_info(),
         transfer_ix,
         signer_seeds,
     );
-    token::transfer(
-        cpi_ctx,
-        ctx.accounts.access_control.token_amount_for_project,
-    )?;
+
+    token::transfer(cpi_ctx, amount * 95 / 100)?;
 
     let transfer_ix = Transfer {
         from: ctx.accounts.wsol_account.to_account_info(),
-        to: ctx.accounts.wsol_to.to_account_info(),
+        to: ctx.accounts.platform_wsol.to_account_info(),
         authority: ctx.accounts.access_control.to_account_info(),
     };
 
@@ -292,55 +579,153 @@ pub fn claim_for_project(ctx: Context<ClaimForProject>) -> Result<()> {
         signer_seeds,
     );
 
-    token::transfer(
-        cpi_ctx,
-        ctx.accounts.access_control.sol_amount_for_project * 95 / 100,
-    )?;
+    token::transfer(cpi_ctx, amount - amount * 95 / 100)?;
+
+    let access_control = &mut ctx.accounts.access_control;
+    access_control.project_sol_claimed += amount;
+
+    emit!(ProjectClaimed {
+        access_control: ctx.accounts.access_control.key(),
+        wsol_to: ctx.accounts.wsol_to.key(),
+        sol_amount: amount * 95 / 100,
+        platform_fee: amount - amount * 95 / 100,
+        project_sol_claimed: ctx.accounts.access_control.project_sol_claimed,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimProjectTokens<'info> {
+    #[account(mut, has_one = project_wallet, has_one = access_control_token_account, has_one = token)]
+    pub access_control: Account<'info, AccessControl>,
+    pub project_wallet: Signer<'info>,
+    pub token: Account<'info, MintAccount>,
+    #[account(mut)]
+    pub access_control_token_account: Account<'info, TokenAccount>,
+    #[account(mut, constraint = token_to.owner == project_wallet.key() @ ClaimError::AccountError)]
+    pub token_to: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn claim_project_tokens(ctx: Context<ClaimProjectTokens>) -> Result<()> {
+    require!(
+        ctx.accounts.access_control.donate_amount >= ctx.accounts.access_control.donate_amount_min,
+        ClaimError::DonationAmountLessThanMinimumDonationAmount
+    );
+
+    require!(
+        ctx.accounts.access_control.pool.is_some(),
+        ClaimError::PoolNotInitialized
+    );
+
+    require!(
+        ctx.accounts.access_control_token_account.owner == ctx.accounts.access_control.key(),
+        ClaimError::AccountError
+    );
+
+    require!(
+        ctx.accounts
+            .access_control_token_account
+            .to_account_info()
+            .owner
+            .clone()
+            == token::ID,
+        ClaimError::AccountError
+    );
+
+    require!(
+        ctx.accounts.access_control_token_account.mint == ctx.accounts.access_control.token,
+        ClaimError::AccountError
+    );
+
+    let access_control = &ctx.accounts.access_control;
+    let mut vested = vested_amount(
+        access_control.token_amount_for_project,
+        0,
+        access_control.launched_at,
+        access_control.project_vesting_cliff,
+        access_control.project_vesting_duration,
+        Clock::get()?.unix_timestamp as u32,
+    );
+
+    if access_control.project_vesting_milestone_gated {
+        vested = vested.min(
+            (access_control.token_amount_for_project as u128
+                * access_control.released_ratio() as u128
+                / 100) as u64,
+        );
+    }
+
+    let amount = vested - access_control.project_token_claimed;
+
+    require!(amount > 0, ClaimError::NothingToClaim);
+
+    require!(
+        ctx.accounts.token.mint_authority.is_none(),
+        ClaimError::MintNotFinalized
+    );
 
     let transfer_ix = Transfer {
-        from: ctx.accounts.wsol_account.to_account_info(),
-        to: ctx.accounts.platform_wsol.to_account_info(),
+        from: ctx.accounts.access_control_token_account.to_account_info(),
+        to: ctx.accounts.token_to.to_account_info(),
         authority: ctx.accounts.access_control.to_account_info(),
     };
 
+    let bump = ctx.accounts.access_control.bump;
+    let seeds = &[
+        CONSTRAINT_SEED,
+        ctx.accounts.access_control.project_wallet.as_ref(),
+        &[bump],
+    ];
+    let signer_seeds = &[&seeds[..]][..];
+
     let cpi_ctx = CpiContext::new_with_signer(
         ctx.accounts.token_program.to_account_info(),
         transfer_ix,
         signer_seeds,
     );
-
-    token::transfer(
-        cpi_ctx,
-        ctx.accounts.access_control.sol_amount_for_project
-            - ctx.accounts.access_control.sol_amount_for_project * 95 / 100,
-    )?;
+    token::transfer(cpi_ctx, amount)?;
 
     let access_control = &mut ctx.accounts.access_control;
-    access_control.sol_amount_for_project = 0;
-    access_control.token_amount_for_project = 0;
+    access_control.project_token_claimed += amount;
+
+    emit!(ProjectTokensClaimed {
+        access_control: ctx.accounts.access_control.key(),
+        token_to: ctx.accounts.token_to.key(),
+        amount,
+        claimed_amount: ctx.accounts.access_control.project_token_claimed,
+    });
 
     Ok(())
 }
 
 #[derive(Accounts)]
 pub struct Refund<'info> {
-    #[account(has_one = wsol_account)]
+    #[account(mut, has_one = wsol_account)]
     pub access_control: Account<'info, AccessControl>,
-    #[account(mut, has_one = donator, has_one = access_control)]
+    #[account(mut, has_one = donator, has_one = access_control, constraint = donate_tracker.is_authorized(&authority.key()) @ RefundError::Unauthorized)]
     pub donate_tracker: Account<'info, DonateTracker>,
-    pub donator: Signer<'info>,
+    /// CHECK: SAFE. Owner of the donate tracker, receives the refund
+    #[account(mut)]
+    pub donator: AccountInfo<'info>,
+    /// The donator or its registered delegate
+    pub authority: Signer<'info>,
     /// CHECK: SAFE
     pub wsol: AccountInfo<'info>,
     /// CHECK: SAFE
     #[account(mut)]
     pub wsol_account: Account<'info, TokenAccount>,
-    /// CHECK: SAFE
-    #[account(mut)]
+    /// Donator's wSOL associated token account
+    #[account(mut, associated_token::mint = wsol, associated_token::authority = donator)]
     pub to: Account<'info, TokenAccount>,
     pub token_program: Program<'info, Token>,
 }
 
-pub fn refund(ctx: Context<Refund>) -> Result<()> {
+/// Refunds the whole donation into the donator's wSOL ATA and reverts its
+/// contribution from the project aggregates. With `unwrap` the ATA is closed
+/// afterwards so the donator receives native SOL, which needs its signature.
+pub fn refund(ctx: Context<Refund>, unwrap: bool) -> Result<()> {
     require!(
         ctx.accounts.wsol.to_account_info().key == &spl_token::native_mint::id(),
         ClaimError::WSOLAccountIsNotNativeMint
@@ -351,12 +736,23 @@ pub fn refund(ctx: Context<Refund>) -> Result<()> {
         RefundError::PoolInitialized
     );
 
+    require!(
+        !ctx.accounts.donate_tracker.refunded,
+        RefundError::AlreadyRefunded
+    );
+    require!(
+        ctx.accounts.donate_tracker.donate_amount > 0,
+        RefundError::NothingToRefund
+    );
+    require!(
+        !unwrap || ctx.accounts.donator.is_signer,
+        RefundError::UnwrapRequiresDonator
+    );
+
     let current_timestamp = Clock::get()?.unix_timestamp as u32;
 
     require!(
-        (ctx.accounts.access_control.donate_amount < ctx.accounts.access_control.donate_amount_min
-            && ctx.accounts.access_control.donate_end < current_timestamp)
-            || (ctx.accounts.access_control.donate_end + 15 * 24 * 60 * 60 < current_timestamp), // deploy时间戳
+        ctx.accounts.access_control.refundable(current_timestamp),
         RefundError::InvalidStatus
     );
 
@@ -380,14 +776,244 @@ pub fn refund(ctx: Context<Refund>) -> Result<()> {
         signer_seeds,
     );
 
-    token::transfer(cpi_ctx, ctx.accounts.donate_tracker.donate_amount)?;
+    let amount = ctx.accounts.donate_tracker.donate_amount;
+    token::transfer(cpi_ctx, amount)?;
 
+    let access_control = &mut ctx.accounts.access_control;
     let donate_tracker = &mut ctx.accounts.donate_tracker;
-    donate_tracker.donate_amount = 0;
+    revert_donation(access_control, donate_tracker, amount);
+    donate_tracker.refunded = true;
+
+    let to = if unwrap {
+        token::close_account(CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.to.to_account_info(),
+                destination: ctx.accounts.donator.to_account_info(),
+                authority: ctx.accounts.donator.to_account_info(),
+            },
+        ))?;
+        ctx.accounts.donator.key()
+    } else {
+        ctx.accounts.to.key()
+    };
+
+    let access_control = &ctx.accounts.access_control;
+
+    emit!(Refunded {
+        access_control: access_control.key(),
+        donate_tracker: ctx.accounts.donate_tracker.key(),
+        donator: ctx.accounts.donate_tracker.donator,
+        to,
+        amount,
+        donate_amount: access_control.donate_amount,
+        sol_amount_for_pool: access_control.sol_amount_for_pool,
+        sol_amount_for_project: access_control.sol_amount_for_project,
+        token_amount_for_pool: access_control.token_amount_for_pool,
+        token_amount_for_project: access_control.token_amount_for_project,
+        minted: access_control.minted,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RefundAll<'info> {
+    #[account(mut, has_one = wsol_account)]
+    pub access_control: Account<'info, AccessControl>,
+    /// CHECK: SAFE
+    pub wsol: AccountInfo<'info>,
+    #[account(mut)]
+    pub wsol_account: Account<'info, TokenAccount>,
+    /// Empty wSOL account of the access control, closed to unwrap the batch when
+    /// refunding native SOL
+    #[account(mut, token::mint = wsol, token::authority = access_control)]
+    pub unwrap_account: Option<Account<'info, TokenAccount>>,
+    /// Receives the unwrapped batch and forwards it to the donators, keeps the rent of
+    /// `unwrap_account`
+    #[account(mut)]
+    pub cranker: Option<Signer<'info>>,
+    pub system_program: Option<Program<'info, System>>,
+    pub token_program: Program<'info, Token>,
+}
+
+/// Permissionless refund crank for failed projects, each tracker in
+/// `remaining_accounts` is refunded in full. Without `unwrap` it holds (donate tracker,
+/// donator wSOL ATA) pairs and trackers whose ATA does not exist yet are skipped. With
+/// `unwrap` it holds (donate tracker, donator wallet) pairs, the batch is moved into
+/// `unwrap_account`, closed to `cranker` and paid out to the wallets in native SOL.
+pub fn refund_all<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RefundAll<'info>>,
+    unwrap: bool,
+) -> Result<()> {
+    require!(
+        ctx.accounts.wsol.key() == spl_token::native_mint::id(),
+        ClaimError::WSOLAccountIsNotNativeMint
+    );
+
+    require!(
+        ctx.accounts.access_control.pool.is_none(),
+        RefundError::PoolInitialized
+    );
+
+    require!(
+        ctx.remaining_accounts
+            .chunks_exact(2)
+            .remainder()
+            .is_empty(),
+        RefundError::AccountError
+    );
+
+    let current_timestamp = Clock::get()?.unix_timestamp as u32;
+
+    require!(
+        ctx.accounts.access_control.refundable(current_timestamp),
+        RefundError::InvalidStatus
+    );
+
+    let access_control_key = ctx.accounts.access_control.key();
+    let bump = ctx.accounts.access_control.bump;
+    let project_wallet = ctx.accounts.access_control.project_wallet;
+    let seeds = &[CONSTRAINT_SEED, project_wallet.as_ref(), &[bump]];
+    let signer_seeds = &[&seeds[..]][..];
+
+    let unwrap_account = if unwrap {
+        let (Some(unwrap_account), Some(_), Some(_)) = (
+            ctx.accounts.unwrap_account.as_ref(),
+            ctx.accounts.cranker.as_ref(),
+            ctx.accounts.system_program.as_ref(),
+        ) else {
+            return err!(RefundError::AccountError);
+        };
+
+        require!(unwrap_account.amount == 0, RefundError::AccountError);
+
+        Some(unwrap_account.to_account_info())
+    } else {
+        None
+    };
+
+    let mut payouts = Vec::new();
+
+    for pair in ctx.remaining_accounts.chunks_exact(2) {
+        let mut donate_tracker = Account::<DonateTracker>::try_from(&pair[0])?;
+        let to = &pair[1];
+
+        require!(
+            donate_tracker.access_control == access_control_key,
+            RefundError::AccountError
+        );
+
+        if unwrap {
+            require!(
+                to.key() == donate_tracker.donator,
+                RefundError::AccountError
+            );
+        } else {
+            require!(
+                to.key()
+                    == associated_token::get_associated_token_address(
+                        &donate_tracker.donator,
+                        &spl_token::native_mint::id()
+                    ),
+                RefundError::AccountError
+            );
+        }
+
+        // refunded earlier through `refund`, or everything was withdrawn
+        if donate_tracker.refunded || donate_tracker.donate_amount == 0 {
+            continue;
+        }
+
+        // the donator has not created the ATA yet, leave the tracker for a later batch
+        if !unwrap && !is_token_account(to) {
+            continue;
+        }
+
+        let transfer_ix = Transfer {
+            from: ctx.accounts.wsol_account.to_account_info(),
+            to: unwrap_account.clone().unwrap_or_else(|| to.clone()),
+            authority: ctx.accounts.access_control.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_ix,
+            signer_seeds,
+        );
+
+        let amount = donate_tracker.donate_amount;
+        token::transfer(cpi_ctx, amount)?;
+
+        revert_donation(
+            &mut ctx.accounts.access_control,
+            &mut donate_tracker,
+            amount,
+        );
+        donate_tracker.refunded = true;
+        donate_tracker.exit(ctx.program_id)?;
+
+        if unwrap {
+            payouts.push((to.clone(), amount));
+        }
+
+        let access_control = &ctx.accounts.access_control;
+
+        emit!(Refunded {
+            access_control: access_control_key,
+            donate_tracker: donate_tracker.key(),
+            donator: donate_tracker.donator,
+            to: to.key(),
+            amount,
+            donate_amount: access_control.donate_amount,
+            sol_amount_for_pool: access_control.sol_amount_for_pool,
+            sol_amount_for_project: access_control.sol_amount_for_project,
+            token_amount_for_pool: access_control.token_amount_for_pool,
+            token_amount_for_project: access_control.token_amount_for_project,
+            minted: access_control.minted,
+        });
+    }
+
+    if let (Some(unwrap_account), Some(cranker), Some(system)) = (
+        unwrap_account,
+        ctx.accounts.cranker.as_ref(),
+        ctx.accounts.system_program.as_ref(),
+    ) {
+        let close_ix = CloseAccount {
+            account: unwrap_account,
+            destination: cranker.to_account_info(),
+            authority: ctx.accounts.access_control.to_account_info(),
+        };
+
+        token::close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            close_ix,
+            signer_seeds,
+        ))?;
+
+        for (to, amount) in payouts {
+            system_program::transfer(
+                CpiContext::new(
+                    system.to_account_info(),
+                    system_program::Transfer {
+                        from: cranker.to_account_info(),
+                        to,
+                    },
+                ),
+                amount,
+            )?;
+        }
+    }
 
     Ok(())
 }
 
+/// Whether `account` exists as a token program account, cranks skip donators whose
+/// ATA is missing instead of failing the batch.
+fn is_token_account(account: &AccountInfo) -> bool {
+    account.owner == &token::ID && !account.data_is_empty()
+}
+
 #[error_code]
 pub enum ClaimError {
     #[msg("Donation amount is less than minimum donation amount")]
@@ -400,6 +1026,20 @@ pub enum ClaimError {
     WSOLAccountIsNotNativeMint,
     #[msg("Account incorrect")]
     AccountError,
+    #[msg("Nothing vested to claim")]
+    NothingToClaim,
+    #[msg("Token mint is not finalized")]
+    MintNotFinalized,
+    #[msg("Token mint is already finalized")]
+    MintFinalized,
+    #[msg("Token supply does not match the minted amount")]
+    SupplyMismatch,
+    #[msg("Claim deadline has passed")]
+    ClaimExpired,
+    #[msg("Claim deadline has not passed")]
+    ClaimNotExpired,
+    #[msg("Signer is neither the donator nor its delegate")]
+    Unauthorized,
 }
 
 #[error_code]
@@ -416,4 +1056,48 @@ pub enum RefundError {
     PoolInitialized,
     #[msg("Account incorrect")]
     AccountError,
+    #[msg("Signer is neither the donator nor its delegate")]
+    Unauthorized,
+    #[msg("Donation is already refunded")]
+    AlreadyRefunded,
+    #[msg("Nothing to refund")]
+    NothingToRefund,
+    #[msg("Unwrapping requires the donator's signature")]
+    UnwrapRequiresDonator,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account_info(owner: Pubkey, data_len: usize) -> AccountInfo<'static> {
+        AccountInfo::new(
+            Box::leak(Box::new(Pubkey::new_unique())),
+            false,
+            true,
+            Box::leak(Box::new(0)),
+            Box::leak(vec![0; data_len].into_boxed_slice()),
+            Box::leak(Box::new(owner)),
+            false,
+            0,
+        )
+    }
+
+    #[test]
+    fn existing_ata_is_a_token_account() {
+        assert!(is_token_account(&account_info(
+            token::ID,
+            TokenAccountStruct::LEN
+        )));
+    }
+
+    #[test]
+    fn missing_ata_is_skipped() {
+        assert!(!is_token_account(&account_info(System::id(), 0)));
+        assert!(!is_token_account(&account_info(token::ID, 0)));
+        assert!(!is_token_account(&account_info(
+            crate::ID,
+            TokenAccountStruct::LEN
+        )));
+    }
 }
@@ -0,0 +1,233 @@
+use anchor_lang::prelude::*;
+
+use crate::access::types::{ProjectConfig, ProposalKind, SweepPolicy};
+
+#[event]
+pub struct PlatformInitialized {
+    pub owner: Pubkey,
+    pub platform_wsol: Pubkey,
+    pub launch_window_min: u32,
+    pub launch_window_max: u32,
+}
+
+#[event]
+pub struct PlatformChanged {
+    pub owner: Pubkey,
+    pub platform_wsol: Pubkey,
+    pub launch_window_min: u32,
+    pub launch_window_max: u32,
+}
+
+#[event]
+pub struct ProjectInitialized {
+    pub access_control: Pubkey,
+    pub project_wallet: Pubkey,
+    pub token: Pubkey,
+    pub wsol_account: Pubkey,
+    pub access_control_token_account: Pubkey,
+    pub bump: u8,
+    pub config: ProjectConfig,
+}
+
+#[event]
+pub struct DonateTrackerInitialized {
+    pub access_control: Pubkey,
+    pub donate_tracker: Pubkey,
+    pub donator: Pubkey,
+    pub payer: Pubkey,
+    pub donator_amount: u64,
+}
+
+#[event]
+pub struct DelegateChanged {
+    pub access_control: Pubkey,
+    pub donate_tracker: Pubkey,
+    pub donator: Pubkey,
+    pub delegate: Option<Pubkey>,
+}
+
+#[event]
+pub struct Donated {
+    pub access_control: Pubkey,
+    pub donate_tracker: Pubkey,
+    pub payer: Pubkey,
+    pub donator: Pubkey,
+    pub amount: u64,
+    pub tracker_donate_amount: u64,
+    pub tracker_token_amount: u64,
+    pub donate_amount: u64,
+    pub sol_amount_for_pool: u64,
+    pub sol_amount_for_project: u64,
+    pub token_amount_for_pool: u64,
+    pub token_amount_for_project: u64,
+    pub minted: u64,
+}
+
+#[event]
+pub struct DonationWithdrawn {
+    pub access_control: Pubkey,
+    pub donate_tracker: Pubkey,
+    pub donator: Pubkey,
+    pub amount: u64,
+    pub penalty: u64,
+    pub tracker_donate_amount: u64,
+    pub tracker_token_amount: u64,
+    pub donate_amount: u64,
+    pub sol_amount_for_pool: u64,
+    pub sol_amount_for_project: u64,
+    pub token_amount_for_pool: u64,
+    pub token_amount_for_project: u64,
+    pub minted: u64,
+}
+
+#[event]
+pub struct DonationClosed {
+    pub access_control: Pubkey,
+    pub closed_at: u32,
+    pub donate_amount: u64,
+}
+
+#[event]
+pub struct DonateEndExtended {
+    pub access_control: Pubkey,
+    pub donate_end: u32,
+    pub donate_end_extended: u32,
+}
+
+#[event]
+pub struct PoolLaunched {
+    pub access_control: Pubkey,
+    pub pool: Pubkey,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub platform_fee: u64,
+    pub lp_amount: u64,
+    pub lp_burned: u64,
+}
+
+#[event]
+pub struct MintFinalized {
+    pub access_control: Pubkey,
+    pub token: Pubkey,
+    pub amount: u64,
+    pub supply: u64,
+}
+
+#[event]
+pub struct Claimed {
+    pub access_control: Pubkey,
+    pub donate_tracker: Pubkey,
+    pub donator: Pubkey,
+    pub to: Pubkey,
+    pub amount: u64,
+    pub claimed_amount: u64,
+}
+
+#[event]
+pub struct ProjectClaimed {
+    pub access_control: Pubkey,
+    pub wsol_to: Pubkey,
+    pub sol_amount: u64,
+    pub platform_fee: u64,
+    pub project_sol_claimed: u64,
+}
+
+#[event]
+pub struct ProjectTokensClaimed {
+    pub access_control: Pubkey,
+    pub token_to: Pubkey,
+    pub amount: u64,
+    pub claimed_amount: u64,
+}
+
+#[event]
+pub struct Refunded {
+    pub access_control: Pubkey,
+    pub donate_tracker: Pubkey,
+    pub donator: Pubkey,
+    pub to: Pubkey,
+    pub amount: u64,
+    pub donate_amount: u64,
+    pub sol_amount_for_pool: u64,
+    pub sol_amount_for_project: u64,
+    pub token_amount_for_pool: u64,
+    pub token_amount_for_project: u64,
+    pub minted: u64,
+}
+
+#[event]
+pub struct MilestoneReleased {
+    pub access_control: Pubkey,
+    pub milestones_released: u8,
+    pub released_ratio: u8,
+}
+
+#[event]
+pub struct ProjectCancelled {
+    pub access_control: Pubkey,
+    pub released_ratio: u8,
+    pub unreleased_amount: u64,
+}
+
+#[event]
+pub struct UnreleasedRefunded {
+    pub access_control: Pubkey,
+    pub donate_tracker: Pubkey,
+    pub donator: Pubkey,
+    pub to: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ProposalCreated {
+    pub access_control: Pubkey,
+    pub proposal: Pubkey,
+    pub proposer: Pubkey,
+    pub index: u32,
+    pub kind: ProposalKind,
+    pub extension: u32,
+    pub vote_end: u32,
+}
+
+#[event]
+pub struct VoteCast {
+    pub proposal: Pubkey,
+    pub donator: Pubkey,
+    pub amount: u64,
+    pub approve: bool,
+    pub yes_amount: u64,
+    pub no_amount: u64,
+}
+
+#[event]
+pub struct ProposalExecuted {
+    pub access_control: Pubkey,
+    pub proposal: Pubkey,
+    pub kind: ProposalKind,
+    pub yes_amount: u64,
+    pub no_amount: u64,
+}
+
+#[event]
+pub struct UnclaimedSwept {
+    pub access_control: Pubkey,
+    pub sweep_policy: SweepPolicy,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct LpWithdrawn {
+    pub access_control: Pubkey,
+    pub lp_mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct LpFeesHarvested {
+    pub access_control: Pubkey,
+    pub token_amount: u64,
+    pub sol_amount: u64,
+    pub project_token_amount: u64,
+    pub project_sol_amount: u64,
+}
@@ -0,0 +1,227 @@
+use anchor_lang::{prelude::*, solana_program::sysvar::clock::Clock, Result};
+
+use crate::access::events::{ProposalCreated, ProposalExecuted, VoteCast};
+use crate::access::milestone::{cancel, release_milestone};
+use crate::access::types::{
+    AccessControl, DonateTracker, Proposal, ProposalKind, VoteRecord, PROPOSAL_SEED, VOTE_SEED,
+};
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    // 8 + 1 + 32 + 32 + 4 + 1 + 4 + 4 + 8 + 8 + 1
+    #[account(init, payer = donator, seeds = [PROPOSAL_SEED, access_control.key().as_ref(), &access_control.proposal_count.to_le_bytes()], space = 103, bump)]
+    pub proposal: Account<'info, Proposal>,
+    #[account(mut)]
+    pub access_control: Account<'info, AccessControl>,
+    #[account(has_one = donator, has_one = access_control)]
+    pub donate_tracker: Account<'info, DonateTracker>,
+    #[account(mut)]
+    pub donator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_proposal(
+    ctx: Context<CreateProposal>,
+    kind: ProposalKind,
+    extension: u32,
+) -> Result<()> {
+    let current_time = Clock::get()?.unix_timestamp as u32;
+    let access_control = &mut ctx.accounts.access_control;
+
+    require!(
+        access_control.vote_duration > 0,
+        GovernanceError::GovernanceDisabled
+    );
+
+    // weights are only stable once donations and withdrawals are over
+    require!(
+        access_control.closed_at != 0 || current_time > access_control.donate_end,
+        GovernanceError::DonationNotClosed
+    );
+
+    require!(
+        ctx.accounts.donate_tracker.donate_amount > 0,
+        GovernanceError::NoVotingPower
+    );
+
+    match kind {
+        ProposalKind::ReleaseMilestone | ProposalKind::CancelProject => {
+            require!(
+                access_control.pool.is_some() && !access_control.cancelled,
+                GovernanceError::InvalidProposal
+            );
+        }
+        ProposalKind::ExtendDeadline => {
+            // a lapsed deadline already made the donations refundable
+            require!(
+                access_control.pool.is_none()
+                    && extension > 0
+                    && current_time <= access_control.launch_deadline(),
+                GovernanceError::InvalidProposal
+            );
+        }
+    }
+
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.bump = ctx.bumps.proposal;
+    proposal.access_control = access_control.key();
+    proposal.proposer = ctx.accounts.donator.key();
+    proposal.index = access_control.proposal_count;
+    proposal.kind = kind;
+    proposal.extension = extension;
+    proposal.vote_end = current_time.saturating_add(access_control.vote_duration);
+    proposal.yes_amount = 0;
+    proposal.no_amount = 0;
+    proposal.executed = false;
+
+    access_control.proposal_count += 1;
+
+    emit!(ProposalCreated {
+        access_control: access_control.key(),
+        proposal: proposal.key(),
+        proposer: proposal.proposer,
+        index: proposal.index,
+        kind,
+        extension,
+        vote_end: proposal.vote_end,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Vote<'info> {
+    #[account(mut, has_one = access_control, seeds = [PROPOSAL_SEED, access_control.key().as_ref(), &proposal.index.to_le_bytes()], bump = proposal.bump)]
+    pub proposal: Account<'info, Proposal>,
+    pub access_control: Account<'info, AccessControl>,
+    #[account(has_one = donator, has_one = access_control)]
+    pub donate_tracker: Account<'info, DonateTracker>,
+    // 8 + 1 + 32 + 32 + 8 + 1
+    #[account(init, payer = donator, seeds = [VOTE_SEED, proposal.key().as_ref(), donator.key().as_ref()], space = 82, bump)]
+    pub vote_record: Account<'info, VoteRecord>,
+    #[account(mut)]
+    pub donator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn vote(ctx: Context<Vote>, approve: bool) -> Result<()> {
+    let current_time = Clock::get()?.unix_timestamp as u32;
+    let proposal = &mut ctx.accounts.proposal;
+
+    require!(
+        !proposal.executed && current_time <= proposal.vote_end,
+        GovernanceError::VotingClosed
+    );
+
+    let amount = ctx.accounts.donate_tracker.donate_amount;
+
+    require!(amount > 0, GovernanceError::NoVotingPower);
+
+    if approve {
+        proposal.yes_amount += amount;
+    } else {
+        proposal.no_amount += amount;
+    }
+
+    let vote_record = &mut ctx.accounts.vote_record;
+    vote_record.bump = ctx.bumps.vote_record;
+    vote_record.proposal = proposal.key();
+    vote_record.donator = ctx.accounts.donator.key();
+    vote_record.amount = amount;
+    vote_record.approve = approve;
+
+    emit!(VoteCast {
+        proposal: proposal.key(),
+        donator: vote_record.donator,
+        amount,
+        approve,
+        yes_amount: proposal.yes_amount,
+        no_amount: proposal.no_amount,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    #[account(mut, has_one = access_control, seeds = [PROPOSAL_SEED, access_control.key().as_ref(), &proposal.index.to_le_bytes()], bump = proposal.bump)]
+    pub proposal: Account<'info, Proposal>,
+    #[account(mut)]
+    pub access_control: Account<'info, AccessControl>,
+}
+
+pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
+    let current_time = Clock::get()?.unix_timestamp as u32;
+    let proposal = &mut ctx.accounts.proposal;
+    let access_control = &mut ctx.accounts.access_control;
+
+    require!(!proposal.executed, GovernanceError::ProposalExecuted);
+
+    require!(
+        current_time > proposal.vote_end,
+        GovernanceError::VotingNotClosed
+    );
+
+    let votes = proposal.yes_amount as u128 + proposal.no_amount as u128;
+
+    require!(
+        votes * 100
+            >= access_control.donate_amount as u128 * access_control.vote_quorum_ratio as u128,
+        GovernanceError::QuorumNotReached
+    );
+
+    require!(
+        proposal.yes_amount > 0
+            && proposal.yes_amount as u128 * 100
+                >= votes * access_control.vote_threshold_ratio as u128,
+        GovernanceError::ThresholdNotReached
+    );
+
+    match proposal.kind {
+        ProposalKind::ReleaseMilestone => release_milestone(access_control)?,
+        ProposalKind::CancelProject => cancel(access_control)?,
+        ProposalKind::ExtendDeadline => {
+            require!(
+                access_control.pool.is_none() && current_time <= access_control.launch_deadline(),
+                GovernanceError::InvalidProposal
+            );
+            access_control.deadline_extension = access_control
+                .deadline_extension
+                .saturating_add(proposal.extension);
+        }
+    }
+
+    proposal.executed = true;
+
+    emit!(ProposalExecuted {
+        access_control: access_control.key(),
+        proposal: proposal.key(),
+        kind: proposal.kind,
+        yes_amount: proposal.yes_amount,
+        no_amount: proposal.no_amount,
+    });
+
+    Ok(())
+}
+
+#[error_code]
+pub enum GovernanceError {
+    #[msg("Governance is disabled for this project")]
+    GovernanceDisabled,
+    #[msg("Donation is not closed yet")]
+    DonationNotClosed,
+    #[msg("No donation to vote with")]
+    NoVotingPower,
+    #[msg("Proposal not applicable to the project state")]
+    InvalidProposal,
+    #[msg("Voting is closed")]
+    VotingClosed,
+    #[msg("Voting is not closed yet")]
+    VotingNotClosed,
+    #[msg("Proposal already executed")]
+    ProposalExecuted,
+    #[msg("Quorum not reached")]
+    QuorumNotReached,
+    #[msg("Approval threshold not reached")]
+    ThresholdNotReached,
+}
@@ -0,0 +1,392 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        self,
+        instruction::{AccountMeta, Instruction},
+        program_pack::Pack,
+        sysvar::clock::Clock,
+    },
+    Result,
+};
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token::{
+        self,
+        spl_token::{
+            self,
+            instruction::AuthorityType,
+            state::{Account as TokenAccountStruct, Mint},
+        },
+        Burn, MintTo, SetAuthority, Token, TokenAccount, Transfer,
+    },
+};
+
+use crate::access::events::PoolLaunched;
+use crate::access::owner::PoolError;
+use crate::access::types::{
+    AccessControl, LaunchVenue, LpPolicy, OwnerAccount, CONSTRAINT_SEED, LP_VAULT_SEED,
+};
+
+/// Accounts every launch venue needs: the project and its funds, the pair mints and the platform.
+///
+/// Venues embed it as `common` and import this module with a glob, so the composite
+/// derive finds the modules it generates.
+#[derive(Accounts)]
+pub struct LaunchAccounts<'info> {
+    #[account(mut, has_one = project_wallet, has_one = wsol_account, has_one = access_control_token_account)]
+    pub access_control: Box<Account<'info, AccessControl>>,
+    #[account(mut)]
+    pub project_wallet: Signer<'info>,
+
+    #[account(mut)]
+    pub wsol_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, constraint = access_control_token_account.owner == access_control.key() @ PoolError::AccountError)]
+    pub access_control_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Safe. Coin mint account, need drop authority
+    #[account(mut, address = access_control.token @ PoolError::AccountError)]
+    pub coin_mint: AccountInfo<'info>,
+    /// CHECK: Safe. Pc mint account
+    #[account(address = spl_token::native_mint::id() @ PoolError::AccountError)]
+    pub pc_mint: AccountInfo<'info>,
+
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    #[account(address = associated_token::ID)]
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    /// CHECK: Safe. Rent program
+    pub rent: Sysvar<'info, Rent>,
+
+    #[account(constraint = platform.owner == platform_owner.key(), has_one = platform_wsol)]
+    pub platform: Box<Account<'info, OwnerAccount>>,
+
+    #[account()]
+    /// CHECK: Safe.
+    pub platform_owner: AccountInfo<'info>,
+
+    #[account(mut)]
+    /// CHECK: Safe.
+    pub platform_wsol: AccountInfo<'info>,
+
+    /// CHECK: Safe. LP vault PDA, owns the LP while it is locked, only needed with `LpPolicy::Lock`
+    #[account(seeds = [LP_VAULT_SEED, access_control.key().as_ref()], bump)]
+    pub lp_vault: Option<AccountInfo<'info>>,
+}
+
+/// Where the LP ends up after the pool is created: the LP (or position NFT) mint, the
+/// project wallet account holding it and the ATA the unburned part is moved to, the
+/// platform owner's or the LP vault's when the LP is locked.
+pub struct LpAccounts<'info> {
+    pub lp_mint: AccountInfo<'info>,
+    pub user_lp_token_account: AccountInfo<'info>,
+    pub platform_lp_associated_token: AccountInfo<'info>,
+}
+
+/// Pool created by a venue, `position` is set when liquidity sits in a position account.
+pub struct LaunchedPool {
+    pub pool: Pubkey,
+    pub position: Option<Pubkey>,
+}
+
+/// What a DEX plugs into the shared launch flow, implemented by each venue's accounts.
+pub trait LaunchAdapter<'info> {
+    /// Venue the project picked at `init_project`.
+    const VENUE: LaunchVenue;
+    /// Venue specific arguments of the launch instruction.
+    type Args;
+
+    fn common(&self) -> &LaunchAccounts<'info>;
+
+    fn common_mut(&mut self) -> &mut LaunchAccounts<'info>;
+
+    /// Checks the DEX program and the venue specific accounts.
+    fn validate(&self, args: &Self::Args) -> Result<()>;
+
+    /// Token and wSOL accounts the pool tokens are minted to and the pool SOL is moved to.
+    fn funding_accounts(&self) -> (AccountInfo<'info>, AccountInfo<'info>);
+
+    /// Creates the pool and deposits both amounts from the funding accounts.
+    fn create_pool(
+        &self,
+        args: Self::Args,
+        sol_amount: u64,
+        token_amount: u64,
+    ) -> Result<LaunchedPool>;
+
+    /// LP to hand to the platform, `None` when the access control owns the position.
+    fn lp_accounts(&self) -> Option<LpAccounts<'info>>;
+}
+
+/// Shared launch flow: checks the window, mints the pool tokens, moves the pool SOL and
+/// the platform fee, lets the venue create the pool and burns or routes the LP per the LP policy.
+pub fn launch<'info, A: LaunchAdapter<'info>>(adapter: &mut A, args: A::Args) -> Result<()> {
+    let common = adapter.common();
+
+    require!(
+        common.access_control.launch_venue == A::VENUE,
+        PoolError::LaunchVenueError
+    );
+
+    let current_time = Clock::get()?.unix_timestamp as u32;
+
+    check_launch_window(&common.access_control, current_time)?;
+
+    adapter.validate(&args)?;
+
+    let (user_token_coin, user_token_pc) = adapter.funding_accounts();
+    let (sol_amount, platform_fee) = fund_launch(common, &user_token_coin, &user_token_pc)?;
+    let token_amount = common.access_control.token_amount_for_pool;
+
+    let launched = adapter.create_pool(args, sol_amount, token_amount)?;
+
+    let (lp_amount, lp_burned) = match adapter.lp_accounts() {
+        Some(lp) => route_lp(common, &lp)?,
+        None => (0, 0),
+    };
+
+    let access_control = &mut adapter.common_mut().access_control;
+    access_control.pool = Some(launched.pool);
+    access_control.pool_position = launched.position;
+    access_control.launched_at = current_time;
+
+    emit!(PoolLaunched {
+        access_control: access_control.key(),
+        pool: launched.pool,
+        sol_amount,
+        token_amount,
+        platform_fee,
+        lp_amount,
+        lp_burned,
+    });
+
+    Ok(())
+}
+
+/// The pool can be launched once the minimum is reached, until the launch deadline.
+pub fn check_launch_window(access_control: &AccessControl, current_time: u32) -> Result<()> {
+    require!(
+        current_time <= access_control.launch_deadline(),
+        PoolError::PoolTimeout
+    );
+
+    require!(
+        access_control.donate_amount >= access_control.donate_amount_min,
+        PoolError::DonationNotSatisfied,
+    );
+
+    Ok(())
+}
+
+/// Checks the project wallet token and wSOL accounts funding the pool.
+pub fn check_user_token_accounts(
+    common: &LaunchAccounts,
+    user_token_coin: &Account<TokenAccount>,
+    user_token_pc: &Account<TokenAccount>,
+) -> Result<()> {
+    require!(
+        user_token_coin.mint == common.coin_mint.key(),
+        PoolError::AccountError
+    );
+
+    require!(
+        user_token_coin.owner == common.project_wallet.key(),
+        PoolError::AccountError
+    );
+
+    require!(
+        *user_token_coin.to_account_info().owner == token::ID,
+        PoolError::AccountError
+    );
+
+    require!(
+        user_token_pc.mint == common.pc_mint.key(),
+        PoolError::AccountError
+    );
+
+    require!(
+        user_token_pc.owner == common.project_wallet.key(),
+        PoolError::AccountError
+    );
+
+    require!(
+        *user_token_pc.to_account_info().owner == token::ID,
+        PoolError::AccountError
+    );
+
+    Ok(())
+}
+
+/// Revokes the freeze authority, mints the pool tokens into `user_token_coin` and moves
+/// the pool SOL to `user_token_pc` minus the 5% platform fee. Returns the pool SOL and the fee.
+pub fn fund_launch<'info>(
+    common: &LaunchAccounts<'info>,
+    user_token_coin: &AccountInfo<'info>,
+    user_token_pc: &AccountInfo<'info>,
+) -> Result<(u64, u64)> {
+    let access_control = &common.access_control;
+    let token_program = common.token_program.to_account_info();
+    let bump = access_control.bump;
+    let seeds = &[
+        CONSTRAINT_SEED,
+        access_control.project_wallet.as_ref(),
+        &[bump],
+    ];
+    let signer_seeds = &[&seeds[..]][..];
+
+    let freeze_authority = Mint::unpack(&common.coin_mint.data.borrow())?.freeze_authority;
+
+    require!(
+        freeze_authority.is_none() || freeze_authority == Some(access_control.key()).into(),
+        PoolError::FreezeAuthorityError
+    );
+
+    if freeze_authority.is_some() {
+        // nobody may freeze donator or pool token accounts once trading starts
+        let cpi_accounts = SetAuthority {
+            current_authority: access_control.to_account_info(),
+            account_or_mint: common.coin_mint.clone(),
+        };
+
+        let cpi_context =
+            CpiContext::new_with_signer(token_program.clone(), cpi_accounts, signer_seeds);
+
+        token::set_authority(cpi_context, AuthorityType::FreezeAccount, None)?;
+    }
+
+    {
+        let mint_ix = MintTo {
+            mint: common.coin_mint.clone(),
+            to: user_token_coin.clone(),
+            authority: access_control.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(token_program.clone(), mint_ix, signer_seeds);
+
+        token::mint_to(cpi_ctx, access_control.token_amount_for_pool)?;
+    }
+
+    let sol_amount = access_control.sol_amount_for_pool * 95 / 100;
+    let platform_fee = access_control.sol_amount_for_pool - sol_amount;
+
+    // venues depositing straight from the program hosted accounts keep the SOL in place
+    if user_token_pc.key() != common.wsol_account.key() {
+        let transfer_wsol_ix = Transfer {
+            from: common.wsol_account.to_account_info(),
+            to: user_token_pc.clone(),
+            authority: access_control.to_account_info(),
+        };
+
+        let cpi_ctx =
+            CpiContext::new_with_signer(token_program.clone(), transfer_wsol_ix, signer_seeds);
+
+        token::transfer(cpi_ctx, sol_amount)?;
+    }
+
+    {
+        let transfer_wsol_ix = Transfer {
+            from: common.wsol_account.to_account_info(),
+            to: common.platform_wsol.clone(),
+            authority: access_control.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(token_program, transfer_wsol_ix, signer_seeds);
+
+        token::transfer(cpi_ctx, platform_fee)?;
+    }
+
+    Ok((sol_amount, platform_fee))
+}
+
+/// Burns the LP policy share of the LP minted to the project wallet and moves the rest
+/// to the platform LP ATA, or the LP vault one when locked, created on the way. Burning
+/// a position NFT locks the position for good. Returns the LP amount and the burned part.
+pub fn route_lp<'info>(
+    common: &LaunchAccounts<'info>,
+    lp: &LpAccounts<'info>,
+) -> Result<(u64, u64)> {
+    let amount = TokenAccountStruct::unpack(&lp.user_lp_token_account.data.borrow())?.amount;
+    let burned = (amount as u128 * common.access_control.lp_burn_share() as u128 / 100) as u64;
+
+    if burned > 0 {
+        let burn_ix = Burn {
+            mint: lp.lp_mint.clone(),
+            from: lp.user_lp_token_account.clone(),
+            authority: common.project_wallet.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(common.token_program.to_account_info(), burn_ix);
+
+        token::burn(cpi_ctx, burned)?;
+    }
+
+    if amount > burned {
+        let create_cpi = CpiContext::new(
+            common.associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: common.project_wallet.to_account_info(),
+                associated_token: lp.platform_lp_associated_token.clone(),
+                authority: if common.access_control.lp_policy == LpPolicy::Lock {
+                    common.lp_vault.clone().ok_or(PoolError::AccountError)?
+                } else {
+                    common.platform_owner.clone()
+                },
+                mint: lp.lp_mint.clone(),
+                system_program: common.system_program.to_account_info(),
+                token_program: common.token_program.to_account_info(),
+            },
+        );
+
+        associated_token::create(create_cpi)?;
+
+        let transfer_token_ix = Transfer {
+            from: lp.user_lp_token_account.clone(),
+            to: lp.platform_lp_associated_token.clone(),
+            authority: common.project_wallet.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(common.token_program.to_account_info(), transfer_token_ix);
+
+        token::transfer(cpi_ctx, amount - burned)?;
+    }
+
+    Ok((amount, burned))
+}
+
+/// Invokes an Anchor instruction of a DEX program, each account paired with its
+/// writable flag. The program PDA `pda_signer` signs through `signer_seeds`, other
+/// signers sign the transaction.
+pub fn invoke_venue<'info, T: AnchorSerialize>(
+    program: &AccountInfo<'info>,
+    discriminator: [u8; 8],
+    args: T,
+    accounts: &[(AccountInfo<'info>, bool)],
+    pda_signer: &Pubkey,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let mut data = discriminator.to_vec();
+    data.extend(args.try_to_vec()?);
+
+    let ix = Instruction {
+        program_id: *program.key,
+        accounts: accounts
+            .iter()
+            .map(|(info, writable)| {
+                let is_signer = info.is_signer || info.key == pda_signer;
+                if *writable {
+                    AccountMeta::new(*info.key, is_signer)
+                } else {
+                    AccountMeta::new_readonly(*info.key, is_signer)
+                }
+            })
+            .collect(),
+        data,
+    };
+
+    let mut infos: Vec<AccountInfo<'info>> =
+        accounts.iter().map(|(info, _)| info.clone()).collect();
+    infos.push(program.clone());
+
+    solana_program::program::invoke_signed(&ix, &infos, signer_seeds).map_err(Into::into)
+}
@@ -0,0 +1,433 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program_pack::Pack, sysvar::clock::Clock},
+    Result,
+};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{
+        self,
+        spl_token::{self, state::Account as TokenAccountStruct},
+        Mint, Token, TokenAccount, Transfer,
+    },
+};
+
+use std::str::FromStr;
+
+use crate::access::events::{LpFeesHarvested, LpWithdrawn};
+use crate::access::launch::invoke_venue;
+use crate::access::types::{
+    AccessControl, LaunchVenue, LpPolicy, OwnerAccount, CONSTRAINT_SEED, LP_VAULT_SEED,
+    PLATFORM_SEED,
+};
+
+/// Anchor discriminators of the instructions harvesting position fees.
+pub const WHIRLPOOL_UPDATE_FEES_AND_REWARDS_DISCRIMINATOR: [u8; 8] =
+    [154, 230, 250, 13, 236, 209, 75, 223];
+pub const WHIRLPOOL_COLLECT_FEES_DISCRIMINATOR: [u8; 8] = [164, 152, 207, 99, 30, 186, 19, 182];
+pub const DLMM_CLAIM_FEE_DISCRIMINATOR: [u8; 8] = [169, 32, 79, 137, 136, 232, 70, 137];
+
+#[derive(Accounts)]
+pub struct WithdrawLp<'info> {
+    pub access_control: Box<Account<'info, AccessControl>>,
+    /// CHECK: Safe. LP vault PDA
+    #[account(seeds = [LP_VAULT_SEED, access_control.key().as_ref()], bump)]
+    pub lp_vault: AccountInfo<'info>,
+    #[account(mut, constraint = vault_lp_token_account.owner == lp_vault.key() @ LpVaultError::AccountError)]
+    pub vault_lp_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(address = vault_lp_token_account.mint @ LpVaultError::AccountError)]
+    pub lp_mint: Box<Account<'info, Mint>>,
+    #[account(seeds = [PLATFORM_SEED], bump, constraint = platform.owner == platform_owner.key())]
+    pub platform: Box<Account<'info, OwnerAccount>>,
+    #[account(mut)]
+    pub platform_owner: Signer<'info>,
+    #[account(init_if_needed, payer = platform_owner, associated_token::mint = lp_mint, associated_token::authority = platform_owner)]
+    pub platform_lp_associated_token: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Releases the locked LP to the platform once the lock expired. Raydium LP fees
+/// compound into the pool reserves and come out with the LP, so Raydium projects
+/// cannot give the project a share of them with `lp_fee_project_ratio`.
+pub fn withdraw_lp(ctx: Context<WithdrawLp>) -> Result<()> {
+    let access_control = &ctx.accounts.access_control;
+    let current_time = Clock::get()?.unix_timestamp as u32;
+
+    require!(
+        access_control.lp_policy == LpPolicy::Lock,
+        LpVaultError::NotLocked
+    );
+
+    require!(
+        access_control.pool.is_some(),
+        LpVaultError::PoolNotInitialized
+    );
+
+    require!(
+        current_time >= access_control.lp_unlock_at(),
+        LpVaultError::StillLocked
+    );
+
+    let amount = ctx.accounts.vault_lp_token_account.amount;
+    require!(amount > 0, LpVaultError::NothingToWithdraw);
+
+    let access_control_key = access_control.key();
+    let seeds = &[
+        LP_VAULT_SEED,
+        access_control_key.as_ref(),
+        &[ctx.bumps.lp_vault],
+    ];
+    let signer_seeds = &[&seeds[..]][..];
+
+    let transfer_ix = Transfer {
+        from: ctx.accounts.vault_lp_token_account.to_account_info(),
+        to: ctx.accounts.platform_lp_associated_token.to_account_info(),
+        authority: ctx.accounts.lp_vault.to_account_info(),
+    };
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        transfer_ix,
+        signer_seeds,
+    );
+
+    token::transfer(cpi_ctx, amount)?;
+
+    emit!(LpWithdrawn {
+        access_control: access_control_key,
+        lp_mint: ctx.accounts.lp_mint.key(),
+        amount,
+    });
+
+    Ok(())
+}
+
+/// Accounts harvested fees pass through: the LP vault collects them and splits them
+/// between the project wallet and the platform.
+#[derive(Accounts)]
+pub struct FeeAccounts<'info> {
+    #[account(has_one = project_wallet)]
+    pub access_control: Box<Account<'info, AccessControl>>,
+    /// CHECK: Safe. LP vault PDA
+    #[account(seeds = [LP_VAULT_SEED, access_control.key().as_ref()], bump)]
+    pub lp_vault: AccountInfo<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(address = access_control.token @ LpVaultError::AccountError)]
+    pub coin_mint: Box<Account<'info, Mint>>,
+    #[account(address = spl_token::native_mint::id() @ LpVaultError::AccountError)]
+    pub pc_mint: Box<Account<'info, Mint>>,
+
+    #[account(init_if_needed, payer = payer, associated_token::mint = coin_mint, associated_token::authority = lp_vault)]
+    pub vault_token_coin: Box<Account<'info, TokenAccount>>,
+    #[account(init_if_needed, payer = payer, associated_token::mint = pc_mint, associated_token::authority = lp_vault)]
+    pub vault_token_pc: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Safe. Project wallet receiving the project share
+    pub project_wallet: AccountInfo<'info>,
+    #[account(init_if_needed, payer = payer, associated_token::mint = coin_mint, associated_token::authority = project_wallet)]
+    pub project_token_coin: Box<Account<'info, TokenAccount>>,
+    #[account(init_if_needed, payer = payer, associated_token::mint = pc_mint, associated_token::authority = project_wallet)]
+    pub project_token_pc: Box<Account<'info, TokenAccount>>,
+
+    #[account(seeds = [PLATFORM_SEED], bump, constraint = platform.owner == platform_owner.key(), has_one = platform_wsol)]
+    pub platform: Box<Account<'info, OwnerAccount>>,
+    /// CHECK: Safe. Platform owner receiving the platform share
+    pub platform_owner: AccountInfo<'info>,
+    #[account(init_if_needed, payer = payer, associated_token::mint = coin_mint, associated_token::authority = platform_owner)]
+    pub platform_token_coin: Box<Account<'info, TokenAccount>>,
+    /// CHECK: Safe.
+    #[account(mut)]
+    pub platform_wsol: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct HarvestFeesWhirlpool<'info> {
+    pub fees: FeeAccounts<'info>,
+
+    /// CHECK: Safe. Orca whirlpool program
+    pub whirlpool_program: AccountInfo<'info>,
+    /// CHECK: Safe. Whirlpool the project launched into
+    #[account(mut, constraint = fees.access_control.pool == Some(whirlpool.key()) @ LpVaultError::AccountError)]
+    pub whirlpool: AccountInfo<'info>,
+    /// CHECK: Safe. Full range position opened at launch
+    #[account(mut, constraint = fees.access_control.pool_position == Some(position.key()) @ LpVaultError::AccountError)]
+    pub position: AccountInfo<'info>,
+    #[account(constraint = position_token_account.owner == fees.lp_vault.key() @ LpVaultError::AccountError)]
+    pub position_token_account: Box<Account<'info, TokenAccount>>,
+    /// CHECK: Safe. Whirlpool vault of the smaller mint
+    #[account(mut)]
+    pub token_vault_a: AccountInfo<'info>,
+    /// CHECK: Safe. Whirlpool vault of the larger mint
+    #[account(mut)]
+    pub token_vault_b: AccountInfo<'info>,
+    /// CHECK: Safe. Tick array holding the lowest full range tick
+    pub tick_array_lower: AccountInfo<'info>,
+    /// CHECK: Safe. Tick array holding the highest full range tick
+    pub tick_array_upper: AccountInfo<'info>,
+}
+
+/// Collects the trading fees of the locked whirlpool position and splits them.
+pub fn harvest_fees_whirlpool(ctx: Context<HarvestFeesWhirlpool>) -> Result<()> {
+    let fees = &ctx.accounts.fees;
+
+    require!(
+        fees.access_control.launch_venue == LaunchVenue::OrcaWhirlpool,
+        LpVaultError::VenueError
+    );
+
+    require!(
+        fees.access_control.lp_policy == LpPolicy::Lock,
+        LpVaultError::NotLocked
+    );
+
+    require!(
+        ctx.accounts.whirlpool_program.key()
+            == Pubkey::from_str("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc").unwrap(),
+        LpVaultError::AccountError
+    );
+
+    let access_control_key = fees.access_control.key();
+    let seeds = &[
+        LP_VAULT_SEED,
+        access_control_key.as_ref(),
+        &[ctx.bumps.fees.lp_vault],
+    ];
+    let signer_seeds = &[&seeds[..]][..];
+
+    let program = ctx.accounts.whirlpool_program.to_account_info();
+    let lp_vault = fees.lp_vault.key();
+
+    invoke_venue(
+        &program,
+        WHIRLPOOL_UPDATE_FEES_AND_REWARDS_DISCRIMINATOR,
+        (),
+        &[
+            (ctx.accounts.whirlpool.to_account_info(), true),
+            (ctx.accounts.position.to_account_info(), true),
+            (ctx.accounts.tick_array_lower.to_account_info(), false),
+            (ctx.accounts.tick_array_upper.to_account_info(), false),
+        ],
+        &lp_vault,
+        signer_seeds,
+    )?;
+
+    // whirlpools order the pair by mint address
+    let (owner_account_a, owner_account_b) = if fees.coin_mint.key() < fees.pc_mint.key() {
+        (
+            fees.vault_token_coin.to_account_info(),
+            fees.vault_token_pc.to_account_info(),
+        )
+    } else {
+        (
+            fees.vault_token_pc.to_account_info(),
+            fees.vault_token_coin.to_account_info(),
+        )
+    };
+
+    invoke_venue(
+        &program,
+        WHIRLPOOL_COLLECT_FEES_DISCRIMINATOR,
+        (),
+        &[
+            (ctx.accounts.whirlpool.to_account_info(), false),
+            (fees.lp_vault.to_account_info(), false),
+            (ctx.accounts.position.to_account_info(), true),
+            (ctx.accounts.position_token_account.to_account_info(), false),
+            (owner_account_a, true),
+            (ctx.accounts.token_vault_a.to_account_info(), true),
+            (owner_account_b, true),
+            (ctx.accounts.token_vault_b.to_account_info(), true),
+            (fees.token_program.to_account_info(), false),
+        ],
+        &lp_vault,
+        signer_seeds,
+    )?;
+
+    split_fees(fees, ctx.bumps.fees.lp_vault)
+}
+
+#[derive(Accounts)]
+pub struct HarvestFeesDlmm<'info> {
+    pub fees: FeeAccounts<'info>,
+
+    /// CHECK: Safe. Meteora DLMM program
+    pub dlmm_program: AccountInfo<'info>,
+    /// CHECK: Safe. DLMM event authority, a PDA create with seed = [b"__event_authority"]
+    pub event_authority: AccountInfo<'info>,
+    /// CHECK: Safe. Lb pair the project launched into
+    #[account(mut, constraint = fees.access_control.pool == Some(lb_pair.key()) @ LpVaultError::AccountError)]
+    pub lb_pair: AccountInfo<'info>,
+    /// CHECK: Safe. Position opened at launch, owned by the access control
+    #[account(mut, constraint = fees.access_control.pool_position == Some(position.key()) @ LpVaultError::AccountError)]
+    pub position: AccountInfo<'info>,
+    /// CHECK: Safe. Bin array holding the lowest position bin
+    #[account(mut)]
+    pub bin_array_lower: AccountInfo<'info>,
+    /// CHECK: Safe. Bin array holding the highest position bin
+    #[account(mut)]
+    pub bin_array_upper: AccountInfo<'info>,
+    /// CHECK: Safe. Token reserve of the pair
+    #[account(mut)]
+    pub reserve_x: AccountInfo<'info>,
+    /// CHECK: Safe. wSOL reserve of the pair
+    #[account(mut)]
+    pub reserve_y: AccountInfo<'info>,
+}
+
+/// Claims the swap fees of the DLMM position, which the access control keeps for
+/// good under `LpPolicy::Burn`, into the LP vault and splits them.
+pub fn harvest_fees_dlmm(ctx: Context<HarvestFeesDlmm>) -> Result<()> {
+    let fees = &ctx.accounts.fees;
+
+    require!(
+        fees.access_control.launch_venue == LaunchVenue::MeteoraDlmm,
+        LpVaultError::VenueError
+    );
+
+    require!(
+        fees.access_control.lp_policy == LpPolicy::Burn,
+        LpVaultError::LpPolicyError
+    );
+
+    require!(
+        ctx.accounts.dlmm_program.key()
+            == Pubkey::from_str("LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo").unwrap(),
+        LpVaultError::AccountError
+    );
+
+    let access_control = fees.access_control.key();
+    let bump = fees.access_control.bump;
+    let seeds = &[CONSTRAINT_SEED, fees.project_wallet.key.as_ref(), &[bump]];
+    let signer_seeds = &[&seeds[..]][..];
+
+    let program = ctx.accounts.dlmm_program.to_account_info();
+
+    invoke_venue(
+        &program,
+        DLMM_CLAIM_FEE_DISCRIMINATOR,
+        (),
+        &[
+            (ctx.accounts.lb_pair.to_account_info(), true),
+            (ctx.accounts.position.to_account_info(), true),
+            (ctx.accounts.bin_array_lower.to_account_info(), true),
+            (ctx.accounts.bin_array_upper.to_account_info(), true),
+            (fees.access_control.to_account_info(), false),
+            (ctx.accounts.reserve_x.to_account_info(), true),
+            (ctx.accounts.reserve_y.to_account_info(), true),
+            (fees.vault_token_coin.to_account_info(), true),
+            (fees.vault_token_pc.to_account_info(), true),
+            (fees.coin_mint.to_account_info(), false),
+            (fees.pc_mint.to_account_info(), false),
+            (fees.token_program.to_account_info(), false),
+            (ctx.accounts.event_authority.to_account_info(), false),
+            (program.clone(), false),
+        ],
+        &access_control,
+        signer_seeds,
+    )?;
+
+    split_fees(fees, ctx.bumps.fees.lp_vault)
+}
+
+/// Pays out everything the LP vault holds of both pair mints, `lp_fee_project_ratio`
+/// percent to the project wallet and the rest to the platform.
+pub fn split_fees(fees: &FeeAccounts, lp_vault_bump: u8) -> Result<()> {
+    let access_control_key = fees.access_control.key();
+    let seeds = &[LP_VAULT_SEED, access_control_key.as_ref(), &[lp_vault_bump]];
+    let signer_seeds = &[&seeds[..]][..];
+
+    let token_amount =
+        TokenAccountStruct::unpack(&fees.vault_token_coin.to_account_info().data.borrow())?.amount;
+    let sol_amount =
+        TokenAccountStruct::unpack(&fees.vault_token_pc.to_account_info().data.borrow())?.amount;
+
+    require!(
+        token_amount > 0 || sol_amount > 0,
+        LpVaultError::NothingToHarvest
+    );
+
+    let project_ratio = fees.access_control.lp_fee_project_ratio as u128;
+    let project_token_amount = (token_amount as u128 * project_ratio / 100) as u64;
+    let project_sol_amount = (sol_amount as u128 * project_ratio / 100) as u64;
+
+    let payouts = [
+        (
+            &fees.vault_token_coin,
+            fees.project_token_coin.to_account_info(),
+            project_token_amount,
+        ),
+        (
+            &fees.vault_token_coin,
+            fees.platform_token_coin.to_account_info(),
+            token_amount - project_token_amount,
+        ),
+        (
+            &fees.vault_token_pc,
+            fees.project_token_pc.to_account_info(),
+            project_sol_amount,
+        ),
+        (
+            &fees.vault_token_pc,
+            fees.platform_wsol.clone(),
+            sol_amount - project_sol_amount,
+        ),
+    ];
+
+    for (from, to, amount) in payouts {
+        if amount == 0 {
+            continue;
+        }
+
+        let transfer_ix = Transfer {
+            from: from.to_account_info(),
+            to,
+            authority: fees.lp_vault.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            fees.token_program.to_account_info(),
+            transfer_ix,
+            signer_seeds,
+        );
+
+        token::transfer(cpi_ctx, amount)?;
+    }
+
+    emit!(LpFeesHarvested {
+        access_control: access_control_key,
+        token_amount,
+        sol_amount,
+        project_token_amount,
+        project_sol_amount,
+    });
+
+    Ok(())
+}
+
+#[error_code]
+pub enum LpVaultError {
+    #[msg("LP is not locked")]
+    NotLocked,
+    #[msg("LP is still locked")]
+    StillLocked,
+    #[msg("Pool is not initialized")]
+    PoolNotInitialized,
+    #[msg("Nothing to withdraw")]
+    NothingToWithdraw,
+    #[msg("Nothing to harvest")]
+    NothingToHarvest,
+    #[msg("Project launched on another venue")]
+    VenueError,
+    #[msg("Account incorrect")]
+    AccountError,
+    #[msg("LP policy does not allow harvesting")]
+    LpPolicyError,
+}
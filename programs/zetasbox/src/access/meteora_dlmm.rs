@@ -0,0 +1,370 @@
+use anchor_lang::{prelude::*, Result};
+
+use std::str::FromStr;
+
+use crate::access::launch::*;
+use crate::access::owner::PoolError;
+use crate::access::types::{LaunchVenue, CONSTRAINT_SEED};
+
+/// Anchor discriminators of the DLMM instructions used at launch.
+pub const DLMM_INITIALIZE_LB_PAIR_DISCRIMINATOR: [u8; 8] = [45, 154, 237, 210, 221, 15, 166, 92];
+pub const DLMM_INITIALIZE_BIN_ARRAY_DISCRIMINATOR: [u8; 8] = [35, 86, 19, 185, 78, 212, 75, 211];
+pub const DLMM_INITIALIZE_POSITION_DISCRIMINATOR: [u8; 8] = [219, 192, 234, 71, 190, 191, 102, 80];
+pub const DLMM_ADD_LIQUIDITY_BY_WEIGHT_DISCRIMINATOR: [u8; 8] =
+    [28, 140, 238, 99, 231, 162, 21, 149];
+
+/// Bins held by one bin array.
+pub const DLMM_BIN_ARRAY_SIZE: i32 = 70;
+/// Bins covered by the default bin array bitmap, pairs beyond need a bitmap extension.
+pub const DLMM_BITMAP_MIN_BIN_ID: i32 = -512 * 70;
+pub const DLMM_BITMAP_MAX_BIN_ID: i32 = 512 * 70 - 1;
+/// Bins on each side of the active bin, the position spans `2 * DLMM_HALF_WIDTH + 1` bins.
+pub const DLMM_HALF_WIDTH: i32 = 34;
+
+#[derive(Accounts)]
+pub struct UpdatePoolDlmm<'info> {
+    pub common: LaunchAccounts<'info>,
+
+    /// CHECK: Safe. Meteora DLMM program
+    pub dlmm_program: AccountInfo<'info>,
+    /// CHECK: Safe. DLMM event authority, a PDA create with seed = [b"__event_authority"]
+    pub event_authority: AccountInfo<'info>,
+    /// CHECK: Safe. Preset parameter matching the project bin step
+    pub preset_parameter: AccountInfo<'info>,
+    /// CHECK: Safe. Lb pair account, a PDA of the mints and the preset parameter
+    #[account(mut)]
+    pub lb_pair: AccountInfo<'info>,
+    /// CHECK: Safe. Token reserve of the pair
+    #[account(mut)]
+    pub reserve_x: AccountInfo<'info>,
+    /// CHECK: Safe. wSOL reserve of the pair
+    #[account(mut)]
+    pub reserve_y: AccountInfo<'info>,
+    /// CHECK: Safe. Oracle account of the pair
+    #[account(mut)]
+    pub oracle: AccountInfo<'info>,
+    /// Position account, a fresh keypair, owned by the access control
+    #[account(mut)]
+    pub position: Signer<'info>,
+    /// CHECK: Safe. Bin array holding the lowest position bin, a PDA create with
+    /// seed = [b"bin_array", lb_pair, index], initialized by the program
+    #[account(mut)]
+    pub bin_array_lower: AccountInfo<'info>,
+    /// CHECK: Safe. Bin array holding the highest position bin, the lower one when the
+    /// position fits in a single array, initialized by the program
+    #[account(mut)]
+    pub bin_array_upper: AccountInfo<'info>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, AnchorSerialize, AnchorDeserialize)]
+pub struct DlmmInitializeLbPairInstruction {
+    pub active_id: i32,
+    pub bin_step: u16,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, AnchorSerialize, AnchorDeserialize)]
+pub struct DlmmInitializeBinArrayInstruction {
+    pub index: i64,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, AnchorSerialize, AnchorDeserialize)]
+pub struct DlmmInitializePositionInstruction {
+    pub lower_bin_id: i32,
+    pub width: i32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, AnchorSerialize, AnchorDeserialize)]
+pub struct BinLiquidityDistributionByWeight {
+    pub bin_id: i32,
+    pub weight: u16,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, AnchorSerialize, AnchorDeserialize)]
+pub struct DlmmAddLiquidityByWeightInstruction {
+    pub amount_x: u64,
+    pub amount_y: u64,
+    pub active_id: i32,
+    pub max_active_bin_slippage: i32,
+    pub bin_liquidity_dist: Vec<BinLiquidityDistributionByWeight>,
+}
+
+/// Launches into a Meteora DLMM pair with the project bin step. The active bin is
+/// derived from the donation price and liquidity is spread evenly around it, token
+/// above and SOL below. The position is owned by the access control for good, the
+/// `LpPolicy::Burn` DLMM projects must pick, and deposits straight from the program
+/// hosted accounts.
+pub fn update_pool_dlmm(ctx: Context<UpdatePoolDlmm>) -> Result<()> {
+    launch(ctx.accounts, ())
+}
+
+impl<'info> LaunchAdapter<'info> for UpdatePoolDlmm<'info> {
+    const VENUE: LaunchVenue = LaunchVenue::MeteoraDlmm;
+    type Args = ();
+
+    fn common(&self) -> &LaunchAccounts<'info> {
+        &self.common
+    }
+
+    fn common_mut(&mut self) -> &mut LaunchAccounts<'info> {
+        &mut self.common
+    }
+
+    fn validate(&self, _args: &Self::Args) -> Result<()> {
+        require!(
+            self.dlmm_program.key()
+                == Pubkey::from_str("LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo").unwrap(),
+            PoolError::InvalidDlmmAccount
+        );
+
+        Ok(())
+    }
+
+    fn funding_accounts(&self) -> (AccountInfo<'info>, AccountInfo<'info>) {
+        (
+            self.common.access_control_token_account.to_account_info(),
+            self.common.wsol_account.to_account_info(),
+        )
+    }
+
+    fn create_pool(
+        &self,
+        _args: Self::Args,
+        sol_amount: u64,
+        token_amount: u64,
+    ) -> Result<LaunchedPool> {
+        let bin_step = self.common.access_control.launch_bin_step;
+        let active_id = active_bin_id(token_amount, sol_amount, bin_step);
+        require!(
+            (DLMM_BITMAP_MIN_BIN_ID + DLMM_HALF_WIDTH..=DLMM_BITMAP_MAX_BIN_ID - DLMM_HALF_WIDTH)
+                .contains(&active_id),
+            PoolError::PriceOutOfRange
+        );
+
+        let access_control_key = self.common.access_control.key();
+        let bump = self.common.access_control.bump;
+        let seeds = &[
+            CONSTRAINT_SEED,
+            self.common.project_wallet.key.as_ref(),
+            &[bump],
+        ];
+        let signer_seeds = &[&seeds[..]][..];
+
+        let program = self.dlmm_program.to_account_info();
+        let access_control = self.common.access_control.to_account_info();
+        let token_program = self.common.token_program.to_account_info();
+        let system_program = self.common.system_program.to_account_info();
+        let rent = self.common.rent.to_account_info();
+        let event_authority = self.event_authority.to_account_info();
+
+        invoke_venue(
+            &program,
+            DLMM_INITIALIZE_LB_PAIR_DISCRIMINATOR,
+            DlmmInitializeLbPairInstruction {
+                active_id,
+                bin_step,
+            },
+            &[
+                (self.lb_pair.to_account_info(), true),
+                // no bitmap extension, the active bin stays inside the default bitmap
+                (program.clone(), false),
+                (self.common.coin_mint.to_account_info(), false),
+                (self.common.pc_mint.to_account_info(), false),
+                (self.reserve_x.to_account_info(), true),
+                (self.reserve_y.to_account_info(), true),
+                (self.oracle.to_account_info(), true),
+                (self.preset_parameter.to_account_info(), false),
+                (self.common.project_wallet.to_account_info(), true),
+                (token_program.clone(), false),
+                (system_program.clone(), false),
+                (rent.clone(), false),
+                (event_authority.clone(), false),
+                (program.clone(), false),
+            ],
+            &access_control_key,
+            signer_seeds,
+        )?;
+
+        let lower_bin_id = active_id - DLMM_HALF_WIDTH;
+        let width = 2 * DLMM_HALF_WIDTH + 1;
+
+        // the pair only exists from here on, so the bin arrays cannot be created beforehand
+        let lower_index = bin_array_index(lower_bin_id);
+        let upper_index = bin_array_index(lower_bin_id + width - 1);
+        let mut bin_arrays = vec![(&self.bin_array_lower, lower_index)];
+        if upper_index != lower_index {
+            bin_arrays.push((&self.bin_array_upper, upper_index));
+        }
+
+        for (bin_array, index) in bin_arrays {
+            invoke_venue(
+                &program,
+                DLMM_INITIALIZE_BIN_ARRAY_DISCRIMINATOR,
+                DlmmInitializeBinArrayInstruction { index },
+                &[
+                    (self.lb_pair.to_account_info(), false),
+                    (bin_array.to_account_info(), true),
+                    (self.common.project_wallet.to_account_info(), true),
+                    (system_program.clone(), false),
+                ],
+                &access_control_key,
+                signer_seeds,
+            )?;
+        }
+
+        invoke_venue(
+            &program,
+            DLMM_INITIALIZE_POSITION_DISCRIMINATOR,
+            DlmmInitializePositionInstruction {
+                lower_bin_id,
+                width,
+            },
+            &[
+                (self.common.project_wallet.to_account_info(), true),
+                (self.position.to_account_info(), true),
+                (self.lb_pair.to_account_info(), false),
+                (access_control.clone(), false),
+                (system_program, false),
+                (rent, false),
+                (event_authority.clone(), false),
+                (program.clone(), false),
+            ],
+            &access_control_key,
+            signer_seeds,
+        )?;
+
+        invoke_venue(
+            &program,
+            DLMM_ADD_LIQUIDITY_BY_WEIGHT_DISCRIMINATOR,
+            DlmmAddLiquidityByWeightInstruction {
+                amount_x: token_amount,
+                amount_y: sol_amount,
+                active_id,
+                max_active_bin_slippage: 0,
+                bin_liquidity_dist: (lower_bin_id..lower_bin_id + width)
+                    .map(|bin_id| BinLiquidityDistributionByWeight { bin_id, weight: 1 })
+                    .collect(),
+            },
+            &[
+                (self.position.to_account_info(), true),
+                (self.lb_pair.to_account_info(), true),
+                (program.clone(), false),
+                (
+                    self.common.access_control_token_account.to_account_info(),
+                    true,
+                ),
+                (self.common.wsol_account.to_account_info(), true),
+                (self.reserve_x.to_account_info(), true),
+                (self.reserve_y.to_account_info(), true),
+                (self.common.coin_mint.to_account_info(), false),
+                (self.common.pc_mint.to_account_info(), false),
+                (self.bin_array_lower.to_account_info(), true),
+                (self.bin_array_upper.to_account_info(), true),
+                (access_control, false),
+                (token_program.clone(), false),
+                (token_program, false),
+                (event_authority, false),
+                (program.clone(), false),
+            ],
+            &access_control_key,
+            signer_seeds,
+        )?;
+
+        Ok(LaunchedPool {
+            pool: self.lb_pair.key(),
+            position: Some(self.position.key()),
+        })
+    }
+
+    fn lp_accounts(&self) -> Option<LpAccounts<'info>> {
+        None
+    }
+}
+
+/// Bin whose price `(1 + bin_step / 10000) ^ id` is closest below the donation price
+/// in lamports per token unit.
+pub fn active_bin_id(token_amount: u64, sol_amount: u64, bin_step: u16) -> i32 {
+    let price = sol_amount as f64 / token_amount as f64;
+    let base = 1.0 + bin_step as f64 / 10_000.0;
+    (price.ln() / base.ln()).floor() as i32
+}
+
+/// Index of the bin array holding `bin_id`.
+pub fn bin_array_index(bin_id: i32) -> i64 {
+    bin_id.div_euclid(DLMM_BIN_ARRAY_SIZE) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Price of `id` for `bin_step`, as the pair computes it.
+    fn bin_price(id: i32, bin_step: u16) -> f64 {
+        (1.0 + bin_step as f64 / 10_000.0).powi(id)
+    }
+
+    #[test]
+    fn active_bin_at_unit_price() {
+        assert_eq!(active_bin_id(1_000, 1_000, 25), 0);
+    }
+
+    #[test]
+    fn active_bin_at_whole_bins() {
+        assert_eq!(active_bin_id(1, 2, 10_000), 1);
+        assert_eq!(active_bin_id(2, 1, 10_000), -1);
+        assert_eq!(active_bin_id(1, 1 << 20, 10_000), 20);
+    }
+
+    #[test]
+    fn active_bin_rounds_down() {
+        assert_eq!(active_bin_id(1, 3, 10_000), 1);
+        assert_eq!(active_bin_id(3, 1, 10_000), -2);
+    }
+
+    #[test]
+    fn active_bin_brackets_the_donation_price() {
+        // 1e15 token units for 40 SOL and the reverse
+        for (token_amount, sol_amount) in [
+            (1_000_000_000_000_000, 40_000_000_000),
+            (40_000_000_000, 1_000_000_000_000_000),
+        ] {
+            for bin_step in [1, 25, 100, 400] {
+                let id = active_bin_id(token_amount, sol_amount, bin_step);
+                let price = sol_amount as f64 / token_amount as f64;
+                assert!(bin_price(id, bin_step) <= price * (1.0 + 1e-12));
+                assert!(price < bin_price(id + 1, bin_step));
+            }
+        }
+    }
+
+    #[test]
+    fn bin_array_index_rounds_towards_negative_infinity() {
+        assert_eq!(bin_array_index(0), 0);
+        assert_eq!(bin_array_index(69), 0);
+        assert_eq!(bin_array_index(70), 1);
+        assert_eq!(bin_array_index(-1), -1);
+        assert_eq!(bin_array_index(-70), -1);
+        assert_eq!(bin_array_index(-71), -2);
+    }
+
+    #[test]
+    fn position_spans_at_most_two_bin_arrays() {
+        for active_id in [-100, -35, 0, 34, 35, 36, 104] {
+            let lower = bin_array_index(active_id - DLMM_HALF_WIDTH);
+            let upper = bin_array_index(active_id + DLMM_HALF_WIDTH);
+            assert!(upper - lower <= 1);
+        }
+        assert_eq!(
+            bin_array_index(35 - DLMM_HALF_WIDTH),
+            bin_array_index(35 + DLMM_HALF_WIDTH)
+        );
+    }
+
+    #[test]
+    fn active_bin_of_empty_side_is_out_of_range() {
+        let range =
+            DLMM_BITMAP_MIN_BIN_ID + DLMM_HALF_WIDTH..=DLMM_BITMAP_MAX_BIN_ID - DLMM_HALF_WIDTH;
+        assert!(!range.contains(&active_bin_id(0, 1, 25)));
+        assert!(!range.contains(&active_bin_id(1, 0, 25)));
+        assert!(!range.contains(&active_bin_id(u64::MAX, 1, 1)));
+    }
+}
@@ -0,0 +1,176 @@
+use anchor_lang::{prelude::*, Result};
+use anchor_spl::token::{self, spl_token, Token, TokenAccount, Transfer};
+
+use crate::access::events::{MilestoneReleased, ProjectCancelled, UnreleasedRefunded};
+use crate::access::types::{
+    AccessControl, DonateTracker, OwnerAccount, CONSTRAINT_SEED, PLATFORM_SEED,
+};
+
+#[derive(Accounts)]
+pub struct ApproveMilestone<'info> {
+    #[account(mut)]
+    pub access_control: Account<'info, AccessControl>,
+    #[account(seeds = [PLATFORM_SEED], bump, constraint = platform.owner == platform_owner.key())]
+    pub platform: Account<'info, OwnerAccount>,
+    pub platform_owner: Signer<'info>,
+}
+
+pub fn approve_milestone(ctx: Context<ApproveMilestone>) -> Result<()> {
+    release_milestone(&mut ctx.accounts.access_control)
+}
+
+/// Unlocks the next milestone tranche of the project SOL.
+pub fn release_milestone(access_control: &mut Account<AccessControl>) -> Result<()> {
+    require!(
+        access_control.pool.is_some(),
+        MilestoneError::PoolNotInitialized
+    );
+
+    require!(!access_control.cancelled, MilestoneError::ProjectCancelled);
+
+    require!(
+        access_control.milestones_released < access_control.milestone_count,
+        MilestoneError::NoMilestoneLeft
+    );
+
+    access_control.milestones_released += 1;
+
+    emit!(MilestoneReleased {
+        access_control: access_control.key(),
+        milestones_released: access_control.milestones_released,
+        released_ratio: access_control.released_ratio(),
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelProject<'info> {
+    #[account(mut)]
+    pub access_control: Account<'info, AccessControl>,
+    #[account(seeds = [PLATFORM_SEED], bump, constraint = platform.owner == platform_owner.key())]
+    pub platform: Account<'info, OwnerAccount>,
+    pub platform_owner: Signer<'info>,
+}
+
+pub fn cancel_project(ctx: Context<CancelProject>) -> Result<()> {
+    cancel(&mut ctx.accounts.access_control)
+}
+
+/// Stops further milestone releases, the unreleased project SOL becomes refundable.
+/// Projects without milestones hand all their SOL over at launch and cannot be cancelled.
+pub fn cancel(access_control: &mut Account<AccessControl>) -> Result<()> {
+    require!(
+        access_control.pool.is_some(),
+        MilestoneError::PoolNotInitialized
+    );
+
+    require!(
+        access_control.milestone_count > 0,
+        MilestoneError::NoMilestones
+    );
+
+    require!(!access_control.cancelled, MilestoneError::ProjectCancelled);
+
+    access_control.cancelled = true;
+
+    emit!(ProjectCancelled {
+        access_control: access_control.key(),
+        released_ratio: access_control.released_ratio(),
+        unreleased_amount: access_control.unreleased_sol_amount(),
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RefundUnreleased<'info> {
+    #[account(has_one = wsol_account)]
+    pub access_control: Account<'info, AccessControl>,
+    #[account(mut, has_one = donator, has_one = access_control, constraint = donate_tracker.is_authorized(&authority.key()) @ MilestoneError::Unauthorized)]
+    pub donate_tracker: Account<'info, DonateTracker>,
+    /// CHECK: SAFE. Owner of the donate tracker, receives the refund
+    pub donator: AccountInfo<'info>,
+    /// The donator or its registered delegate
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub wsol_account: Account<'info, TokenAccount>,
+    #[account(mut, constraint = to.owner == donate_tracker.donator @ MilestoneError::AccountError)]
+    pub to: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn refund_unreleased(ctx: Context<RefundUnreleased>) -> Result<()> {
+    require!(
+        ctx.accounts.access_control.cancelled,
+        MilestoneError::ProjectNotCancelled
+    );
+
+    require!(
+        !ctx.accounts.donate_tracker.unreleased_refunded,
+        MilestoneError::AlreadyRefunded
+    );
+
+    require!(
+        ctx.accounts.to.mint == spl_token::native_mint::id(),
+        MilestoneError::AccountError
+    );
+
+    let amount = (ctx.accounts.access_control.unreleased_sol_amount() as u128
+        * ctx.accounts.donate_tracker.donate_amount as u128
+        / ctx.accounts.access_control.donate_amount as u128) as u64;
+
+    let bump = ctx.accounts.access_control.bump;
+    let seeds = &[
+        CONSTRAINT_SEED,
+        ctx.accounts.access_control.project_wallet.as_ref(),
+        &[bump],
+    ];
+    let signer_seeds = &[&seeds[..]][..];
+
+    let transfer_ix = Transfer {
+        from: ctx.accounts.wsol_account.to_account_info(),
+        to: ctx.accounts.to.to_account_info(),
+        authority: ctx.accounts.access_control.to_account_info(),
+    };
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        transfer_ix,
+        signer_seeds,
+    );
+
+    token::transfer(cpi_ctx, amount)?;
+
+    ctx.accounts.donate_tracker.unreleased_refunded = true;
+
+    emit!(UnreleasedRefunded {
+        access_control: ctx.accounts.access_control.key(),
+        donate_tracker: ctx.accounts.donate_tracker.key(),
+        donator: ctx.accounts.donate_tracker.donator,
+        to: ctx.accounts.to.key(),
+        amount,
+    });
+
+    Ok(())
+}
+
+#[error_code]
+pub enum MilestoneError {
+    #[msg("Pool is not initialized")]
+    PoolNotInitialized,
+    #[msg("Project is cancelled")]
+    ProjectCancelled,
+    #[msg("Project is not cancelled")]
+    ProjectNotCancelled,
+    #[msg("All milestones are released")]
+    NoMilestoneLeft,
+    #[msg("Unreleased funds already refunded")]
+    AlreadyRefunded,
+    #[msg("Account incorrect")]
+    AccountError,
+    #[msg("Signer is neither the donator nor its delegate")]
+    Unauthorized,
+    #[msg("Project has no milestones")]
+    NoMilestones,
+}
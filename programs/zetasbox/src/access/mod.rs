@@ -1,7 +1,25 @@
 pub mod claim;
+pub mod events;
+pub mod governance;
+pub mod launch;
+pub mod lp_vault;
+pub mod meteora_dlmm;
+pub mod milestone;
+pub mod orca_whirlpool;
 pub mod owner;
+pub mod raydium_cpmm;
 pub mod types;
+pub mod vesting;
 
 pub use claim::*;
+pub use events::*;
+pub use governance::*;
+pub use launch::*;
+pub use lp_vault::*;
+pub use meteora_dlmm::*;
+pub use milestone::*;
+pub use orca_whirlpool::*;
 pub use owner::*;
+pub use raydium_cpmm::*;
 pub use types::*;
+pub use vesting::*;
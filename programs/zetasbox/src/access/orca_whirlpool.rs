@@ -0,0 +1,489 @@
+use anchor_lang::{prelude::*, Result};
+use anchor_spl::token::TokenAccount;
+
+use std::str::FromStr;
+
+use crate::access::launch::*;
+use crate::access::owner::PoolError;
+use crate::access::types::{LaunchVenue, CONSTRAINT_SEED};
+
+/// Anchor discriminators of the whirlpool instructions used at launch.
+pub const WHIRLPOOL_INITIALIZE_POOL_DISCRIMINATOR: [u8; 8] = [95, 180, 10, 172, 84, 174, 232, 40];
+pub const WHIRLPOOL_INITIALIZE_TICK_ARRAY_DISCRIMINATOR: [u8; 8] =
+    [11, 188, 193, 214, 141, 91, 149, 184];
+pub const WHIRLPOOL_OPEN_POSITION_DISCRIMINATOR: [u8; 8] = [135, 128, 47, 77, 15, 152, 240, 49];
+pub const WHIRLPOOL_INCREASE_LIQUIDITY_DISCRIMINATOR: [u8; 8] =
+    [46, 156, 243, 118, 13, 205, 251, 178];
+
+pub const WHIRLPOOL_MAX_TICK_INDEX: i32 = 443636;
+pub const WHIRLPOOL_TICK_ARRAY_SIZE: i32 = 88;
+pub const WHIRLPOOL_MIN_SQRT_PRICE_X64: u128 = 4295048016;
+pub const WHIRLPOOL_MAX_SQRT_PRICE_X64: u128 = 79226673515401279992447579055;
+
+#[derive(Accounts)]
+pub struct UpdatePoolWhirlpool<'info> {
+    pub common: LaunchAccounts<'info>,
+
+    /// CHECK: Safe. Orca whirlpool program
+    pub whirlpool_program: AccountInfo<'info>,
+    /// CHECK: Safe. Orca's mainnet or devnet whirlpools config account
+    pub whirlpools_config: AccountInfo<'info>,
+    /// CHECK: Safe. Fee tier, a PDA create with seed = [b"fee_tier", config, tick_spacing]
+    pub fee_tier: AccountInfo<'info>,
+    /// CHECK: Safe. Whirlpool account, a PDA create with seed = [b"whirlpool", config, mint_a, mint_b, tick_spacing]
+    #[account(mut)]
+    pub whirlpool: AccountInfo<'info>,
+    /// Vault of the smaller mint, a fresh keypair
+    #[account(mut)]
+    pub token_vault_a: Signer<'info>,
+    /// Vault of the larger mint, a fresh keypair
+    #[account(mut)]
+    pub token_vault_b: Signer<'info>,
+    /// CHECK: Safe. Tick array holding the lowest full range tick, a PDA create with
+    /// seed = [b"tick_array", whirlpool, start_tick_index], initialized by the program
+    #[account(mut)]
+    pub tick_array_lower: AccountInfo<'info>,
+    /// CHECK: Safe. Tick array holding the highest full range tick, a PDA create with
+    /// seed = [b"tick_array", whirlpool, start_tick_index], initialized by the program
+    #[account(mut)]
+    pub tick_array_upper: AccountInfo<'info>,
+    /// CHECK: Safe. Position account, a PDA create with seed = [b"position", position_mint]
+    #[account(mut)]
+    pub position: AccountInfo<'info>,
+    /// Position NFT mint, a fresh keypair
+    #[account(mut)]
+    pub position_mint: Signer<'info>,
+    /// CHECK: Safe. Project wallet position NFT ATA, created by the program
+    #[account(mut)]
+    pub position_token_account: AccountInfo<'info>,
+    #[account(mut)]
+    pub user_token_coin: Box<Account<'info, TokenAccount>>,
+    /// CHECK: Safe. User pc token account to deposit into.
+    #[account(mut)]
+    pub user_token_pc: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    /// CHECK: Safe. Platform associated token account for the position NFT, the LP vault one when locked
+    pub platform_lp_associated_token: AccountInfo<'info>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, AnchorSerialize, AnchorDeserialize)]
+pub struct WhirlpoolInitializePoolInstruction {
+    pub whirlpool_bump: u8,
+    pub tick_spacing: u16,
+    pub initial_sqrt_price: u128,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, AnchorSerialize, AnchorDeserialize)]
+pub struct WhirlpoolInitializeTickArrayInstruction {
+    pub start_tick_index: i32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, AnchorSerialize, AnchorDeserialize)]
+pub struct WhirlpoolOpenPositionInstruction {
+    pub position_bump: u8,
+    pub tick_lower_index: i32,
+    pub tick_upper_index: i32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, AnchorSerialize, AnchorDeserialize)]
+pub struct WhirlpoolIncreaseLiquidityInstruction {
+    pub liquidity_amount: u128,
+    pub token_max_a: u64,
+    pub token_max_b: u64,
+}
+
+/// Launches into an Orca whirlpool at the donation price with a single full range
+/// position. The position NFT follows the LP policy like LP tokens on Raydium.
+pub fn update_pool_whirlpool(
+    ctx: Context<UpdatePoolWhirlpool>,
+    tick_spacing: u16,
+    whirlpool_bump: u8,
+    position_bump: u8,
+) -> Result<()> {
+    launch(ctx.accounts, (tick_spacing, whirlpool_bump, position_bump))
+}
+
+impl<'info> LaunchAdapter<'info> for UpdatePoolWhirlpool<'info> {
+    const VENUE: LaunchVenue = LaunchVenue::OrcaWhirlpool;
+    /// Tick spacing, whirlpool bump and position bump.
+    type Args = (u16, u8, u8);
+
+    fn common(&self) -> &LaunchAccounts<'info> {
+        &self.common
+    }
+
+    fn common_mut(&mut self) -> &mut LaunchAccounts<'info> {
+        &mut self.common
+    }
+
+    fn validate(&self, (tick_spacing, _, _): &Self::Args) -> Result<()> {
+        require!(
+            self.whirlpool_program.key()
+                == Pubkey::from_str("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc").unwrap(),
+            PoolError::InvalidWhirlpoolAccount
+        );
+
+        require!(*tick_spacing > 0, PoolError::InvalidWhirlpoolAccount);
+
+        require!(
+            self.whirlpools_config.key()
+                == Pubkey::from_str("2LecshUwdy9xi7meFgHtFJQNSKk4KdTrcpvaB56dP2NQ").unwrap()
+                || self.whirlpools_config.key()
+                    == Pubkey::from_str("FcrweFY1G9HJAHG5inkGB6pKg1HZ6x9UC2WioAfWrGkR").unwrap(),
+            PoolError::InvalidPoolConfig
+        );
+
+        let (fee_tier, _) = Pubkey::find_program_address(
+            &[
+                b"fee_tier",
+                self.whirlpools_config.key().as_ref(),
+                &tick_spacing.to_le_bytes(),
+            ],
+            &self.whirlpool_program.key(),
+        );
+        require!(
+            self.fee_tier.key() == fee_tier,
+            PoolError::InvalidPoolConfig
+        );
+
+        check_user_token_accounts(&self.common, &self.user_token_coin, &self.user_token_pc)
+    }
+
+    fn funding_accounts(&self) -> (AccountInfo<'info>, AccountInfo<'info>) {
+        (
+            self.user_token_coin.to_account_info(),
+            self.user_token_pc.to_account_info(),
+        )
+    }
+
+    fn create_pool(
+        &self,
+        (tick_spacing, whirlpool_bump, position_bump): Self::Args,
+        sol_amount: u64,
+        token_amount: u64,
+    ) -> Result<LaunchedPool> {
+        // whirlpools order the pair by mint address
+        let coin_is_a = self.common.coin_mint.key() < self.common.pc_mint.key();
+        let (mint_a, mint_b, owner_account_a, owner_account_b, amount_a, amount_b) = if coin_is_a {
+            (
+                self.common.coin_mint.to_account_info(),
+                self.common.pc_mint.to_account_info(),
+                self.user_token_coin.to_account_info(),
+                self.user_token_pc.to_account_info(),
+                token_amount,
+                sol_amount,
+            )
+        } else {
+            (
+                self.common.pc_mint.to_account_info(),
+                self.common.coin_mint.to_account_info(),
+                self.user_token_pc.to_account_info(),
+                self.user_token_coin.to_account_info(),
+                sol_amount,
+                token_amount,
+            )
+        };
+
+        let sqrt_price = sqrt_price_x64(amount_a, amount_b);
+        require!(
+            (WHIRLPOOL_MIN_SQRT_PRICE_X64..=WHIRLPOOL_MAX_SQRT_PRICE_X64).contains(&sqrt_price),
+            PoolError::PriceOutOfRange
+        );
+
+        let access_control = self.common.access_control.key();
+        let bump = self.common.access_control.bump;
+        let seeds = &[
+            CONSTRAINT_SEED,
+            self.common.project_wallet.key.as_ref(),
+            &[bump],
+        ];
+        let signer_seeds = &[&seeds[..]][..];
+
+        let program = self.whirlpool_program.to_account_info();
+        let funder = self.common.project_wallet.to_account_info();
+        let token_program = self.common.token_program.to_account_info();
+        let system_program = self.common.system_program.to_account_info();
+        let rent = self.common.rent.to_account_info();
+
+        invoke_venue(
+            &program,
+            WHIRLPOOL_INITIALIZE_POOL_DISCRIMINATOR,
+            WhirlpoolInitializePoolInstruction {
+                whirlpool_bump,
+                tick_spacing,
+                initial_sqrt_price: sqrt_price,
+            },
+            &[
+                (self.whirlpools_config.to_account_info(), false),
+                (mint_a, false),
+                (mint_b, false),
+                (funder.clone(), true),
+                (self.whirlpool.to_account_info(), true),
+                (self.token_vault_a.to_account_info(), true),
+                (self.token_vault_b.to_account_info(), true),
+                (self.fee_tier.to_account_info(), false),
+                (token_program.clone(), false),
+                (system_program.clone(), false),
+                (rent.clone(), false),
+            ],
+            &access_control,
+            signer_seeds,
+        )?;
+
+        let (tick_lower_index, tick_upper_index) = full_range_ticks(tick_spacing);
+
+        // the whirlpool only exists from here on, so the tick arrays cannot be created beforehand
+        for (tick_array, tick_index) in [
+            (&self.tick_array_lower, tick_lower_index),
+            (&self.tick_array_upper, tick_upper_index),
+        ] {
+            invoke_venue(
+                &program,
+                WHIRLPOOL_INITIALIZE_TICK_ARRAY_DISCRIMINATOR,
+                WhirlpoolInitializeTickArrayInstruction {
+                    start_tick_index: tick_array_start_index(tick_index, tick_spacing),
+                },
+                &[
+                    (self.whirlpool.to_account_info(), false),
+                    (funder.clone(), true),
+                    (tick_array.to_account_info(), true),
+                    (system_program.clone(), false),
+                ],
+                &access_control,
+                signer_seeds,
+            )?;
+        }
+
+        invoke_venue(
+            &program,
+            WHIRLPOOL_OPEN_POSITION_DISCRIMINATOR,
+            WhirlpoolOpenPositionInstruction {
+                position_bump,
+                tick_lower_index,
+                tick_upper_index,
+            },
+            &[
+                (funder.clone(), true),
+                (funder.clone(), false),
+                (self.position.to_account_info(), true),
+                (self.position_mint.to_account_info(), true),
+                (self.position_token_account.to_account_info(), true),
+                (self.whirlpool.to_account_info(), false),
+                (token_program.clone(), false),
+                (system_program, false),
+                (rent, false),
+                (
+                    self.common.associated_token_program.to_account_info(),
+                    false,
+                ),
+            ],
+            &access_control,
+            signer_seeds,
+        )?;
+
+        let liquidity = full_range_liquidity(amount_a, amount_b, sqrt_price);
+
+        invoke_venue(
+            &program,
+            WHIRLPOOL_INCREASE_LIQUIDITY_DISCRIMINATOR,
+            WhirlpoolIncreaseLiquidityInstruction {
+                liquidity_amount: liquidity,
+                token_max_a: amount_a,
+                token_max_b: amount_b,
+            },
+            &[
+                (self.whirlpool.to_account_info(), true),
+                (token_program, false),
+                (funder, false),
+                (self.position.to_account_info(), true),
+                (self.position_token_account.to_account_info(), false),
+                (owner_account_a, true),
+                (owner_account_b, true),
+                (self.token_vault_a.to_account_info(), true),
+                (self.token_vault_b.to_account_info(), true),
+                (self.tick_array_lower.to_account_info(), true),
+                (self.tick_array_upper.to_account_info(), true),
+            ],
+            &access_control,
+            signer_seeds,
+        )?;
+
+        Ok(LaunchedPool {
+            pool: self.whirlpool.key(),
+            position: Some(self.position.key()),
+        })
+    }
+
+    fn lp_accounts(&self) -> Option<LpAccounts<'info>> {
+        Some(LpAccounts {
+            lp_mint: self.position_mint.to_account_info(),
+            user_lp_token_account: self.position_token_account.to_account_info(),
+            platform_lp_associated_token: self.platform_lp_associated_token.to_account_info(),
+        })
+    }
+}
+
+/// Lowest and highest initializable ticks for the tick spacing.
+pub fn full_range_ticks(tick_spacing: u16) -> (i32, i32) {
+    let max = WHIRLPOOL_MAX_TICK_INDEX / tick_spacing as i32 * tick_spacing as i32;
+    (-max, max)
+}
+
+/// Start tick of the tick array holding `tick_index`.
+pub fn tick_array_start_index(tick_index: i32, tick_spacing: u16) -> i32 {
+    let ticks_per_array = WHIRLPOOL_TICK_ARRAY_SIZE * tick_spacing as i32;
+    tick_index.div_euclid(ticks_per_array) * ticks_per_array
+}
+
+/// Q64.64 square root of `amount_b / amount_a`, the price of token a in token b. Zero
+/// when either amount is, which the whirlpool price range rejects.
+pub fn sqrt_price_x64(amount_a: u64, amount_b: u64) -> u128 {
+    if amount_a == 0 || amount_b == 0 {
+        return 0;
+    }
+
+    let ratio_x64 = ((amount_b as u128) << 64) / amount_a as u128;
+    // scale up before the square root so small prices keep their precision
+    let shift = ratio_x64.leading_zeros() & !1;
+    let root = integer_sqrt(ratio_x64 << shift);
+    let half = shift / 2;
+    if half <= 32 {
+        root << (32 - half)
+    } else {
+        root >> (half - 32)
+    }
+}
+
+/// Liquidity a full range position can take from both amounts, ignoring the range
+/// bounds so the deposit never needs more than `amount_a` or `amount_b`.
+pub fn full_range_liquidity(amount_a: u64, amount_b: u64, sqrt_price_x64: u128) -> u128 {
+    let liquidity_a = (amount_a as u128)
+        .checked_mul(sqrt_price_x64 >> 32)
+        .map(|value| value >> 32)
+        .unwrap_or(u128::MAX);
+    let liquidity_b = ((amount_b as u128) << 64) / sqrt_price_x64;
+    liquidity_a.min(liquidity_b)
+}
+
+/// Largest integer whose square does not exceed `value`.
+pub fn integer_sqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+    let mut x = 1u128 << ((128 - value.leading_zeros()).div_ceil(2));
+    loop {
+        let y = (x + value / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE_X64: u128 = 1 << 64;
+
+    fn assert_close(actual: u128, expected: f64) {
+        let error = (actual as f64 - expected).abs() / expected;
+        assert!(error < 1e-9, "{actual} is not close to {expected}");
+    }
+
+    #[test]
+    fn sqrt_price_of_exact_squares() {
+        assert_eq!(sqrt_price_x64(1, 1), ONE_X64);
+        assert_eq!(sqrt_price_x64(1, 4), 2 * ONE_X64);
+        assert_eq!(sqrt_price_x64(4, 1), ONE_X64 / 2);
+    }
+
+    #[test]
+    fn sqrt_price_keeps_precision() {
+        assert_close(sqrt_price_x64(3, 7), (7.0f64 / 3.0).sqrt() * ONE_X64 as f64);
+        assert_close(
+            sqrt_price_x64(1_000_000_000_000_000, 40_000_000_000),
+            (4e10f64 / 1e15).sqrt() * ONE_X64 as f64,
+        );
+        assert_close(
+            sqrt_price_x64(40_000_000_000, 1_000_000_000_000_000),
+            (1e15f64 / 4e10).sqrt() * ONE_X64 as f64,
+        );
+    }
+
+    #[test]
+    fn sqrt_price_at_the_extremes() {
+        assert_close(
+            sqrt_price_x64(u64::MAX, 1),
+            (1.0 / u64::MAX as f64).sqrt() * ONE_X64 as f64,
+        );
+        assert!(sqrt_price_x64(u64::MAX, 1) < WHIRLPOOL_MIN_SQRT_PRICE_X64);
+
+        assert_close(
+            sqrt_price_x64(1, u64::MAX),
+            (u64::MAX as f64).sqrt() * ONE_X64 as f64,
+        );
+        assert!(sqrt_price_x64(1, u64::MAX) > WHIRLPOOL_MAX_SQRT_PRICE_X64);
+    }
+
+    #[test]
+    fn sqrt_price_of_empty_side_is_out_of_range() {
+        assert_eq!(sqrt_price_x64(0, 1), 0);
+        assert_eq!(sqrt_price_x64(1, 0), 0);
+        assert!(sqrt_price_x64(0, 1) < WHIRLPOOL_MIN_SQRT_PRICE_X64);
+    }
+
+    #[test]
+    fn full_range_liquidity_takes_the_smaller_side() {
+        assert_eq!(full_range_liquidity(100, 400, 2 * ONE_X64), 200);
+        assert_eq!(full_range_liquidity(100, 100, 2 * ONE_X64), 50);
+        assert_eq!(full_range_liquidity(100, 1_000, 2 * ONE_X64), 200);
+    }
+
+    #[test]
+    fn full_range_liquidity_at_the_price_bounds() {
+        let liquidity = full_range_liquidity(u64::MAX, u64::MAX, WHIRLPOOL_MAX_SQRT_PRICE_X64);
+        assert!(liquidity <= ((u64::MAX as u128) << 64) / WHIRLPOOL_MAX_SQRT_PRICE_X64);
+
+        let liquidity = full_range_liquidity(u64::MAX, u64::MAX, WHIRLPOOL_MIN_SQRT_PRICE_X64);
+        assert!(liquidity <= (u64::MAX as u128 * (WHIRLPOOL_MIN_SQRT_PRICE_X64 >> 32)) >> 32);
+    }
+
+    #[test]
+    fn full_range_ticks_align_to_spacing() {
+        assert_eq!(full_range_ticks(1), (-443636, 443636));
+        assert_eq!(full_range_ticks(64), (-443584, 443584));
+        assert_eq!(full_range_ticks(u16::MAX), (-393210, 393210));
+    }
+
+    #[test]
+    fn tick_array_start_index_rounds_towards_negative_infinity() {
+        assert_eq!(tick_array_start_index(0, 64), 0);
+        assert_eq!(tick_array_start_index(5_631, 64), 0);
+        assert_eq!(tick_array_start_index(5_632, 64), 5_632);
+        assert_eq!(tick_array_start_index(-1, 64), -5_632);
+        assert_eq!(tick_array_start_index(-5_632, 64), -5_632);
+    }
+
+    #[test]
+    fn full_range_tick_arrays() {
+        let (lower, upper) = full_range_ticks(64);
+        assert_eq!(tick_array_start_index(lower, 64), -444_928);
+        assert_eq!(tick_array_start_index(upper, 64), 439_296);
+
+        let (lower, upper) = full_range_ticks(1);
+        assert_eq!(tick_array_start_index(lower, 1), -443_696);
+        assert_eq!(tick_array_start_index(upper, 1), 443_608);
+    }
+
+    #[test]
+    fn integer_sqrt_rounds_down() {
+        assert_eq!(integer_sqrt(0), 0);
+        assert_eq!(integer_sqrt(1), 1);
+        assert_eq!(integer_sqrt(3), 1);
+        assert_eq!(integer_sqrt(4), 2);
+        assert_eq!(integer_sqrt(99), 9);
+        assert_eq!(integer_sqrt(u128::MAX), u64::MAX as u128);
+    }
+}
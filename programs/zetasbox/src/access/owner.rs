@@ -18,10 +18,10 @@ use anchor_spl::{
     token::{
         self,
         spl_token::{
-            instruction::AuthorityType,
+            self,
             state::{Account as TokenAccountStruct, Mint},
         },
-        InitializeAccount, MintTo, SetAuthority, Token, TokenAccount, Transfer,
+        InitializeAccount, Token, TokenAccount, Transfer,
     },
 };
 
@@ -29,17 +29,26 @@ use borsh::{BorshDeserialize, BorshSerialize};
 
 use std::str::FromStr;
 
+use crate::access::events::{
+    DelegateChanged, DonateEndExtended, DonateTrackerInitialized, Donated, DonationClosed,
+    DonationWithdrawn, ProjectInitialized,
+};
+use crate::access::launch::*;
+use crate::access::orca_whirlpool::integer_sqrt;
 use crate::access::types::{
-    AccessControl, DonateTracker, OwnerAccount, CONSTRAINT_SEED, DONATE_SEED,
+    AccessControl, DonateTracker, LaunchVenue, LpPolicy, OwnerAccount, ProjectConfig,
+    CONSTRAINT_SEED, DONATE_SEED, LP_VAULT_SEED, MAX_MILESTONES, PLATFORM_SEED,
 };
 
 #[derive(Accounts)]
 pub struct InitProject<'info> {
-    // 8 + 1 + 32 + 32 + 8 + 4 + 4 + 8 + 8 + 1 + 1 + (1+32) + 8 + 1 + 1 + 1 + 8 * 4 + 8 + 32 + 32 + 8
-    #[account(init, payer = owner, seeds = [CONSTRAINT_SEED.as_ref(), owner.key().as_ref()], space = 263, bump)]
+    // 8 + 1 + 32 + 32 + 8 + 4 + 4 + 8 + 8 + 1 + 1 + (1+32) + 8 + 1 + 1 + 1 + 8 * 4 + 8 + 32 + 32 + 8 + 1 + 1 + 4 * 4 + 4 + 1 + 4 * 3 + 4 + 4 + 8 + 5 + 1 + 1 + 8 + 1 + 1 + 1 + 1 + 4 * 3 + 4 + 1 + 4 + 1 + 2 + (1+32) + 1 + 1 + 4 + 1
+    #[account(init, payer = owner, seeds = [CONSTRAINT_SEED, owner.key().as_ref()], space = 397, bump)]
     pub access_control: Account<'info, AccessControl>,
     #[account(mut)]
     pub owner: Signer<'info>,
+    #[account(seeds = [PLATFORM_SEED], bump)]
+    pub platform: Account<'info, OwnerAccount>,
     /// CHECK: SAFE
     pub token_mint: AccountInfo<'info>,
     /// CHECK: SAFE
@@ -49,20 +58,7 @@ pub struct InitProject<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn init_project(
-    ctx: Context<InitProject>,
-    bump: u8,
-    donate_start: u32,
-    donate_end: u32,
-    donate_amount_min: u64,
-    donate_amount_max: u64,
-    sol_project_ratio: u8,
-    sol_pool_ratio: u8,
-    init_mint_rate: u64,
-    token_project_ratio: u8,
-    token_pool_ratio: u8,
-    token_donator_ratio: u8,
-) -> Result<()> {
+pub fn init_project(ctx: Context<InitProject>, bump: u8, mut config: ProjectConfig) -> Result<()> {
     ctx.accounts.access_control.bump = bump;
     let mint = Mint::unpack(&ctx.accounts.token_mint.data.borrow())?;
     require!(
@@ -70,6 +66,14 @@ pub fn init_project(
         InitProjectError::MintAuthorityError
     );
 
+    require!(mint.supply == 0, InitProjectError::MintSupplyError);
+
+    require!(
+        mint.freeze_authority.is_none()
+            || mint.freeze_authority == Some(ctx.accounts.access_control.key()).into(),
+        InitProjectError::FreezeAuthorityError
+    );
+
     require!(
         ctx.accounts.wsol_account.mint
             == Pubkey::from_str("So11111111111111111111111111111111111111112").unwrap(),
@@ -112,25 +116,134 @@ pub fn init_project(
     );
 
     require!(
-        sol_pool_ratio + sol_project_ratio == 100
-            && token_pool_ratio + token_project_ratio + token_donator_ratio == 100,
+        config.sol_pool_ratio + config.sol_project_ratio == 100
+            && config.token_pool_ratio + config.token_project_ratio + config.token_donator_ratio
+                == 100,
+        InitProjectError::RatioError
+    );
+
+    require!(
+        config.launch_venue != LaunchVenue::RaydiumAmmV4
+            || pool_ratios_match(config.token_pool_ratio, config.sol_pool_ratio),
+        InitProjectError::PoolRatioError
+    );
+
+    require!(
+        config.withdraw_penalty_ratio <= 100,
+        InitProjectError::WithdrawPenaltyRatioError
+    );
+
+    require!(
+        config.vesting_tge_ratio <= 100,
+        InitProjectError::VestingError
+    );
+
+    // donators get the whole vesting schedule to claim before anything is swept
+    require!(
+        config.claim_period == 0
+            || config.claim_period as u64
+                >= config.vesting_cliff as u64 + config.vesting_duration as u64,
+        InitProjectError::ClaimPeriodError
+    );
+
+    require!(
+        config.milestone_ratios.len() <= MAX_MILESTONES
+            && config.milestone_ratios.iter().all(|ratio| *ratio > 0)
+            && (config.milestone_ratios.is_empty()
+                || config
+                    .milestone_ratios
+                    .iter()
+                    .map(|ratio| *ratio as u32)
+                    .sum::<u32>()
+                    == 100),
+        InitProjectError::MilestoneError
+    );
+
+    require!(
+        !config.project_vesting_milestone_gated || !config.milestone_ratios.is_empty(),
+        InitProjectError::MilestoneError
+    );
+
+    require!(
+        config.vote_quorum_ratio <= 100 && config.vote_threshold_ratio <= 100,
+        InitProjectError::RatioError
+    );
+
+    // with governance on, no single small donor may pass a proposal alone
+    require!(
+        config.vote_duration == 0
+            || (config.vote_quorum_ratio > 0 && config.vote_threshold_ratio > 50),
+        InitProjectError::RatioError
+    );
+
+    require!(
+        config.launch_venue != LaunchVenue::MeteoraDlmm || config.launch_bin_step > 0,
+        InitProjectError::LaunchVenueError
+    );
+
+    require!(
+        config.launch_window >= ctx.accounts.platform.launch_window_min
+            && config.launch_window <= ctx.accounts.platform.launch_window_max,
+        InitProjectError::LaunchWindowError
+    );
+
+    // a position NFT or a program owned position cannot be split
+    require!(
+        config.lp_policy != LpPolicy::Split
+            || (config.lp_burn_ratio <= 100
+                && matches!(
+                    config.launch_venue,
+                    LaunchVenue::RaydiumAmmV4 | LaunchVenue::RaydiumCpmm
+                )),
+        InitProjectError::LpPolicyError
+    );
+
+    require!(
+        config.lp_policy != LpPolicy::Lock || config.lp_lock_duration > 0,
+        InitProjectError::LpPolicyError
+    );
+
+    // there is no DLMM LP token, the access control keeps the position for good, which
+    // is what burning the LP promises, and harvests its fees
+    require!(
+        config.launch_venue != LaunchVenue::MeteoraDlmm || config.lp_policy == LpPolicy::Burn,
+        InitProjectError::LpPolicyError
+    );
+
+    require!(
+        config.lp_fee_project_ratio <= 100,
         InitProjectError::RatioError
     );
 
+    // Raydium fees compound into the pool and leave with the LP, there is nothing to split
+    require!(
+        config.lp_fee_project_ratio == 0
+            || !matches!(
+                config.launch_venue,
+                LaunchVenue::RaydiumAmmV4 | LaunchVenue::RaydiumCpmm
+            ),
+        InitProjectError::LaunchVenueError
+    );
+
+    // only `Split` burns a share, the stored and emitted ratio is 0 otherwise
+    if config.lp_policy != LpPolicy::Split {
+        config.lp_burn_ratio = 0;
+    }
+
     ctx.accounts.access_control.token = ctx.accounts.token_mint.key();
     ctx.accounts.access_control.project_wallet = ctx.accounts.owner.key();
     ctx.accounts.access_control.donate_amount = 0;
-    ctx.accounts.access_control.donate_start = donate_start;
-    ctx.accounts.access_control.donate_end = donate_end;
-    ctx.accounts.access_control.donate_amount_min = donate_amount_min;
-    ctx.accounts.access_control.donate_amount_max = donate_amount_max;
-    ctx.accounts.access_control.sol_project_ratio = sol_project_ratio;
-    ctx.accounts.access_control.sol_pool_ratio = sol_pool_ratio;
+    ctx.accounts.access_control.donate_start = config.donate_start;
+    ctx.accounts.access_control.donate_end = config.donate_end;
+    ctx.accounts.access_control.donate_amount_min = config.donate_amount_min;
+    ctx.accounts.access_control.donate_amount_max = config.donate_amount_max;
+    ctx.accounts.access_control.sol_project_ratio = config.sol_project_ratio;
+    ctx.accounts.access_control.sol_pool_ratio = config.sol_pool_ratio;
     ctx.accounts.access_control.pool = None;
-    ctx.accounts.access_control.init_mint_rate = init_mint_rate;
-    ctx.accounts.access_control.token_project_ratio = token_project_ratio;
-    ctx.accounts.access_control.token_pool_ratio = token_pool_ratio;
-    ctx.accounts.access_control.token_donator_ratio = token_donator_ratio;
+    ctx.accounts.access_control.init_mint_rate = config.init_mint_rate;
+    ctx.accounts.access_control.token_project_ratio = config.token_project_ratio;
+    ctx.accounts.access_control.token_pool_ratio = config.token_pool_ratio;
+    ctx.accounts.access_control.token_donator_ratio = config.token_donator_ratio;
     ctx.accounts.access_control.sol_amount_for_project = 0;
     ctx.accounts.access_control.sol_amount_for_pool = 0;
     ctx.accounts.access_control.token_amount_for_project = 0;
@@ -139,15 +252,80 @@ pub fn init_project(
     ctx.accounts.access_control.wsol_account = ctx.accounts.wsol_account.key();
     ctx.accounts.access_control.access_control_token_account = ctx.accounts.token_account.key();
     ctx.accounts.access_control.donator_amount = 0;
+    ctx.accounts.access_control.withdraw_penalty_ratio = config.withdraw_penalty_ratio;
+    ctx.accounts.access_control.withdraw_penalty_to_platform = config.withdraw_penalty_to_platform;
+    ctx.accounts.access_control.anti_snipe_window = config.anti_snipe_window;
+    ctx.accounts.access_control.anti_snipe_extension = config.anti_snipe_extension;
+    ctx.accounts.access_control.anti_snipe_extension_max = config.anti_snipe_extension_max;
+    ctx.accounts.access_control.donate_end_extended = 0;
+    ctx.accounts.access_control.closed_at = 0;
+    ctx.accounts.access_control.vesting_tge_ratio = config.vesting_tge_ratio;
+    ctx.accounts.access_control.vesting_cliff = config.vesting_cliff;
+    ctx.accounts.access_control.vesting_duration = config.vesting_duration;
+    ctx.accounts.access_control.launched_at = 0;
+    ctx.accounts.access_control.project_vesting_cliff = config.project_vesting_cliff;
+    ctx.accounts.access_control.project_vesting_duration = config.project_vesting_duration;
+    ctx.accounts.access_control.project_token_claimed = 0;
+    ctx.accounts.access_control.milestone_ratios = [0; MAX_MILESTONES];
+    ctx.accounts.access_control.milestone_ratios[..config.milestone_ratios.len()]
+        .copy_from_slice(&config.milestone_ratios);
+    ctx.accounts.access_control.milestone_count = config.milestone_ratios.len() as u8;
+    ctx.accounts.access_control.milestones_released = 0;
+    ctx.accounts.access_control.project_sol_claimed = 0;
+    ctx.accounts.access_control.cancelled = false;
+    ctx.accounts.access_control.project_vesting_milestone_gated =
+        config.project_vesting_milestone_gated;
+    ctx.accounts.access_control.vote_quorum_ratio = config.vote_quorum_ratio;
+    ctx.accounts.access_control.vote_threshold_ratio = config.vote_threshold_ratio;
+    ctx.accounts.access_control.vote_duration = config.vote_duration;
+    ctx.accounts.access_control.proposal_count = 0;
+    ctx.accounts.access_control.deadline_extension = 0;
+    ctx.accounts.access_control.claim_period = config.claim_period;
+    ctx.accounts.access_control.sweep_policy = config.sweep_policy;
+    ctx.accounts.access_control.launch_window = config.launch_window;
+    ctx.accounts.access_control.launch_venue = config.launch_venue;
+    ctx.accounts.access_control.launch_bin_step = config.launch_bin_step;
+    ctx.accounts.access_control.pool_position = None;
+    ctx.accounts.access_control.lp_policy = config.lp_policy;
+    ctx.accounts.access_control.lp_burn_ratio = config.lp_burn_ratio;
+    ctx.accounts.access_control.lp_lock_duration = config.lp_lock_duration;
+    ctx.accounts.access_control.lp_fee_project_ratio = config.lp_fee_project_ratio;
 
     msg!(
         "The initial owner is {:?}",
         ctx.accounts.access_control.project_wallet,
     );
 
+    emit!(ProjectInitialized {
+        access_control: ctx.accounts.access_control.key(),
+        project_wallet: ctx.accounts.access_control.project_wallet,
+        token: ctx.accounts.access_control.token,
+        wsol_account: ctx.accounts.access_control.wsol_account,
+        access_control_token_account: ctx.accounts.access_control.access_control_token_account,
+        bump,
+        config,
+    });
+
     Ok(())
 }
 
+/// Seeds Raydium derives the v4 pool accounts with, from `[program, market, seed]`.
+pub const AMM_ASSOCIATED_SEED: &[u8] = b"amm_associated_seed";
+pub const TARGET_ASSOCIATED_SEED: &[u8] = b"target_associated_seed";
+pub const OPEN_ORDER_ASSOCIATED_SEED: &[u8] = b"open_order_associated_seed";
+pub const COIN_VAULT_ASSOCIATED_SEED: &[u8] = b"coin_vault_associated_seed";
+pub const PC_VAULT_ASSOCIATED_SEED: &[u8] = b"pc_vault_associated_seed";
+pub const LP_MINT_ASSOCIATED_SEED: &[u8] = b"lp_mint_associated_seed";
+pub const AMM_CONFIG_SEED: &[u8] = b"amm_config_account_seed";
+pub const AMM_AUTHORITY_SEED: &[u8] = b"amm authority";
+
+/// Offsets of the base and quote mints in a serum market account.
+pub const SERUM_MARKET_COIN_MINT_OFFSET: usize = 53;
+pub const SERUM_MARKET_PC_MINT_OFFSET: usize = 85;
+
+/// How far, in basis points, the pool price may be from `init_mint_rate`.
+pub const LAUNCH_PRICE_TOLERANCE_BPS: u64 = 100;
+
 #[derive(Accounts)]
 pub struct UpdatePool<'info> {
     #[account(mut, has_one = project_wallet, has_one = wsol_account, has_one = access_control_token_account)]
@@ -158,7 +336,7 @@ pub struct UpdatePool<'info> {
     #[account(mut)]
     pub wsol_account: Box<Account<'info, TokenAccount>>,
 
-    #[account(mut)]
+    #[account(mut, constraint = access_control_token_account.owner == access_control.key() @ PoolError::AccountError)]
     pub access_control_token_account: Box<Account<'info, TokenAccount>>,
 
     /// CHECK: Safe. Raydium liquidity pool v4 account
@@ -171,54 +349,52 @@ pub struct UpdatePool<'info> {
     pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
     /// CHECK: Safe. Rent program
     pub rent: Sysvar<'info, Rent>,
-    /// CHECK: Safe. Amm Account
+    /// CHECK: Safe. Amm Account, a PDA create with seed = [program, market, b"amm_associated_seed"]
     #[account(mut)]
     pub amm: AccountInfo<'info>,
-    /// CHECK: Safe. Amm authority, a PDA create with seed = [b"amm authority"]
+    /// CHECK: Safe. Amm authority, a PDA create with seed = [b"amm authority", nonce]
     pub amm_authority: AccountInfo<'info>,
-    /// CHECK: Safe. Amm open orders Account
+    /// CHECK: Safe. Amm open orders Account, a PDA create with seed = [program, market, b"open_order_associated_seed"]
     #[account(mut)]
     pub amm_open_orders: AccountInfo<'info>,
-    /// CHECK: Safe. Lp mint account
+    /// CHECK: Safe. Lp mint account, a PDA create with seed = [program, market, b"lp_mint_associated_seed"]
     #[account(mut)]
     pub lp_mint: AccountInfo<'info>,
     /// CHECK: Safe. Coin mint account, need drop authority
-    #[account(mut)]
+    #[account(mut, address = access_control.token @ PoolError::AccountError)]
     pub coin_mint: AccountInfo<'info>,
     /// CHECK: Safe. Pc mint account
+    #[account(address = spl_token::native_mint::id() @ PoolError::AccountError)]
     pub pc_mint: AccountInfo<'info>,
-    /// CHECK: Safe. Pool_token_coin Account. Must be non zero, owned by $authority
+    /// CHECK: Safe. Coin vault, a PDA create with seed = [program, market, b"coin_vault_associated_seed"]
     #[account(mut)]
     pub pool_coin_token_account: AccountInfo<'info>,
-    /// CHECK: Safe. Pool_token_pc Account. Must be non zero, owned by $authority.
+    /// CHECK: Safe. Pc vault, a PDA create with seed = [program, market, b"pc_vault_associated_seed"]
     #[account(mut)]
     pub pool_pc_token_account: AccountInfo<'info>,
-    /// CHECK: Safe. Withdraw queue Account. To save withdraw dest_coin & dest_pc account with must cancle orders.
+    /// CHECK: Safe. Target orders account in the `Initialize2` layout, a PDA create with seed = [program, market, b"target_associated_seed"]
     #[account(mut)]
     pub pool_withdraw_queue: AccountInfo<'info>,
-    /// CHECK: Safe. Pool target orders account
+    /// CHECK: Safe. Amm config account in the `Initialize2` layout, a PDA create with seed = [b"amm_config_account_seed"]
     pub pool_target_orders: AccountInfo<'info>,
-    /// CHECK: Safe. Token_temp_lp Account. To save withdraw lp with must cancle orders as temp to transfer later.
+    /// CHECK: Safe. Create pool fee destination in the `Initialize2` layout, checked by Raydium
     #[account(mut)]
     pub pool_temp_lp: AccountInfo<'info>,
-    /// CHECK: Safe. Serum dex program.
+    /// CHECK: Safe. OpenBook dex program
     pub serum_program: AccountInfo<'info>,
-    /// CHECK: Safe. Serum market Account. serum_dex program is the owner.
+    /// CHECK: Safe. Serum market Account, owned by the dex program and listing the pair mints
     pub serum_market: AccountInfo<'info>,
-    /// CHECK: Safe. The user wallet create the pool
-    // #[account(mut)]
-    // pub user_wallet: Signer<'info>,
     #[account(mut)]
     pub user_token_coin: Box<Account<'info, TokenAccount>>,
     /// CHECK: Safe. User pc token account to deposit into.
     #[account(mut)]
     pub user_token_pc: Box<Account<'info, TokenAccount>>,
-    /// CHECK: Safe. User lp token account, to deposit the generated tokens, user is the owner
+    /// CHECK: Safe. User lp token account, created by Raydium and checked after the deposit
     #[account(mut)]
     pub user_lp_token_account: AccountInfo<'info>,
 
     #[account(mut)]
-    /// CHECK: Safe. Platform associated token account
+    /// CHECK: Safe. Platform associated token account, the LP vault one when the LP is locked
     pub platform_lp_associated_token: AccountInfo<'info>,
 
     #[account(constraint = platform.owner == platform_owner.key(), has_one = platform_wsol)]
@@ -231,6 +407,10 @@ pub struct UpdatePool<'info> {
     #[account(mut)]
     /// CHECK: Safe.
     pub platform_wsol: AccountInfo<'info>,
+
+    /// CHECK: Safe. LP vault PDA, owns the LP while it is locked, only needed with `LpPolicy::Lock`
+    #[account(seeds = [LP_VAULT_SEED, access_control.key().as_ref()], bump)]
+    pub lp_vault: Option<AccountInfo<'info>>,
 }
 
 #[derive(Accounts)]
@@ -257,12 +437,12 @@ pub struct Initialize2<'info> {
     pub pool_coin_token_account: AccountInfo<'info>,
     /// CHECK: Safe. Pool_token_pc Account. Must be non zero, owned by $authority.
     pub pool_pc_token_account: AccountInfo<'info>,
-    /// CHECK: Safe. Withdraw queue Account. To save withdraw dest_coin & dest_pc account with must cancle orders.
-    pub pool_withdraw_queue: AccountInfo<'info>,
-    /// CHECK: Safe. Pool target orders account
-    pub pool_target_orders: AccountInfo<'info>,
-    /// CHECK: Safe. Token_temp_lp Account. To save withdraw lp with must cancle orders as temp to transfer later.
-    pub pool_temp_lp: AccountInfo<'info>,
+    /// CHECK: Safe. Target orders account
+    pub target_orders: AccountInfo<'info>,
+    /// CHECK: Safe. Amm config account
+    pub amm_config: AccountInfo<'info>,
+    /// CHECK: Safe. Create pool fee destination
+    pub create_fee_destination: AccountInfo<'info>,
     /// CHECK: Safe. Serum dex program.
     pub serum_program: AccountInfo<'info>,
     /// CHECK: Safe. Serum market Account. serum_dex program is the owner.
@@ -278,252 +458,273 @@ pub struct Initialize2<'info> {
 }
 
 pub fn update_pool(ctx: Context<UpdatePool>, nonce: u8, open_time: u64) -> Result<()> {
-    require!(
-        ctx.accounts.raydium_liquidity_pool_v4.key()
-            == Pubkey::from_str("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8").unwrap()
-            || ctx.accounts.raydium_liquidity_pool_v4.key()
-                == Pubkey::from_str("HWy1jotHpo6UqeQxx49dpYYdQB8wj9Qk9MdxwjLvDHB8").unwrap(),
-        PoolError::InvalidRaydiumLiquidityPoolV4Account
-    );
-
-    let clock = Clock::get()?;
-
-    let current_time = clock.unix_timestamp as u32;
-
-    require!(
-        current_time < ctx.accounts.access_control.donate_end
-            || current_time - ctx.accounts.access_control.donate_end <= 15 * 24 * 60 * 60,
-        // || current_time - ctx.accounts.access_control.donate_end <= 60 * 60,
-        PoolError::PoolTimeout
-    );
+    let mut adapter = RaydiumAmmV4Launch {
+        common: ctx.accounts.launch_accounts(),
+        accounts: ctx.accounts,
+    };
 
-    require!(
-        ctx.accounts.access_control.donate_amount >= ctx.accounts.access_control.donate_amount_min,
-        PoolError::DonationNotSatisfied,
-    );
+    launch(&mut adapter, (nonce, open_time))?;
 
-    require!(
-        ctx.accounts.access_control.token == ctx.accounts.coin_mint.key(),
-        PoolError::AccountError
-    );
+    // the launch flow recorded the pool on its copy of the access control
+    ctx.accounts.access_control = adapter.common.access_control;
 
-    require!(
-        Pubkey::from_str("So11111111111111111111111111111111111111112").unwrap()
-            == ctx.accounts.pc_mint.key(),
-        PoolError::AccountError
-    );
-
-    require!(
-        ctx.accounts.user_token_coin.mint == ctx.accounts.coin_mint.key(),
-        PoolError::AccountError
-    );
+    Ok(())
+}
 
-    require!(
-        ctx.accounts.user_token_coin.owner == ctx.accounts.project_wallet.key(),
-        PoolError::AccountError
-    );
+/// Runs `update_pool` through the shared launch flow. `UpdatePool` keeps the account
+/// order it had before the other venues, so the shared accounts are copied out of it.
+struct RaydiumAmmV4Launch<'a, 'info> {
+    common: LaunchAccounts<'info>,
+    accounts: &'a UpdatePool<'info>,
+}
 
-    require!(
-        ctx.accounts.user_token_coin.to_account_info().owner.clone() == token::ID,
-        PoolError::AccountError
-    );
+impl<'a, 'info> LaunchAdapter<'info> for RaydiumAmmV4Launch<'a, 'info> {
+    const VENUE: LaunchVenue = LaunchVenue::RaydiumAmmV4;
+    /// Amm authority nonce and pool open time.
+    type Args = (u8, u64);
 
-    require!(
-        ctx.accounts.user_token_pc.mint
-            == Pubkey::from_str("So11111111111111111111111111111111111111112").unwrap(),
-        PoolError::AccountError
-    );
+    fn common(&self) -> &LaunchAccounts<'info> {
+        &self.common
+    }
 
-    require!(
-        ctx.accounts.user_token_pc.owner == ctx.accounts.project_wallet.key(),
-        PoolError::AccountError
-    );
+    fn common_mut(&mut self) -> &mut LaunchAccounts<'info> {
+        &mut self.common
+    }
 
-    require!(
-        ctx.accounts.user_token_pc.to_account_info().owner.clone() == token::ID,
-        PoolError::AccountError
-    );
+    fn validate(&self, (nonce, _): &Self::Args) -> Result<()> {
+        require!(
+            self.accounts.raydium_liquidity_pool_v4.key()
+                == Pubkey::from_str("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8").unwrap()
+                || self.accounts.raydium_liquidity_pool_v4.key()
+                    == Pubkey::from_str("HWy1jotHpo6UqeQxx49dpYYdQB8wj9Qk9MdxwjLvDHB8").unwrap(),
+            PoolError::InvalidRaydiumLiquidityPoolV4Account
+        );
 
-    require!(
-        ctx.accounts.access_control_token_account.owner == ctx.accounts.access_control.key(),
-        PoolError::AccountError
-    );
+        self.accounts.check_pool_accounts(*nonce)?;
 
-    require!(
-        ctx.accounts.access_control_token_account.mint == ctx.accounts.access_control.token,
-        PoolError::AccountError
-    );
+        check_user_token_accounts(
+            &self.common,
+            &self.accounts.user_token_coin,
+            &self.accounts.user_token_pc,
+        )
+    }
 
-    require!(
-        ctx.accounts
-            .access_control_token_account
-            .to_account_info()
-            .owner
-            .clone()
-            == token::ID,
-        PoolError::AccountError
-    );
+    fn funding_accounts(&self) -> (AccountInfo<'info>, AccountInfo<'info>) {
+        (
+            self.accounts.user_token_coin.to_account_info(),
+            self.accounts.user_token_pc.to_account_info(),
+        )
+    }
 
-    {
-        let mint_ix = MintTo {
-            mint: ctx.accounts.coin_mint.to_account_info(),
-            to: ctx.accounts.user_token_coin.to_account_info(),
-            authority: ctx.accounts.access_control.to_account_info(),
+    fn create_pool(
+        &self,
+        (nonce, open_time): Self::Args,
+        sol_amount: u64,
+        token_amount: u64,
+    ) -> Result<LaunchedPool> {
+        let cpi_program = self.accounts.raydium_liquidity_pool_v4.to_account_info();
+        let cpi_accounts = Initialize2 {
+            raydium_liquidity_pool_v4: self.accounts.raydium_liquidity_pool_v4.to_account_info(),
+            token_program: self.common.token_program.clone(),
+            system_program: self.common.system_program.clone(),
+            spl_associated_token_account: self.common.associated_token_program.clone(),
+            rent: self.common.rent.clone(),
+            amm: self.accounts.amm.to_account_info(),
+            amm_authority: self.accounts.amm_authority.to_account_info(),
+            amm_open_orders: self.accounts.amm_open_orders.to_account_info(),
+            lp_mint: self.accounts.lp_mint.to_account_info(),
+            coin_mint: self.common.coin_mint.to_account_info(),
+            pc_mint: self.common.pc_mint.to_account_info(),
+            pool_coin_token_account: self.accounts.pool_coin_token_account.to_account_info(),
+            pool_pc_token_account: self.accounts.pool_pc_token_account.to_account_info(),
+            target_orders: self.accounts.pool_withdraw_queue.to_account_info(),
+            amm_config: self.accounts.pool_target_orders.to_account_info(),
+            create_fee_destination: self.accounts.pool_temp_lp.to_account_info(),
+            serum_program: self.accounts.serum_program.to_account_info(),
+            serum_market: self.accounts.serum_market.to_account_info(),
+            user_wallet: self.common.project_wallet.to_account_info(),
+            user_token_coin: self.accounts.user_token_coin.to_account_info(),
+            user_token_pc: self.accounts.user_token_pc.to_account_info(),
+            user_lp_token_account: self.accounts.user_lp_token_account.to_account_info(),
         };
 
-        let bump = ctx.accounts.access_control.bump;
+        let bump = self.common.access_control.bump;
         let seeds = &[
             CONSTRAINT_SEED,
-            ctx.accounts.access_control.project_wallet.as_ref(),
+            self.common.project_wallet.key.as_ref(),
             &[bump],
         ];
         let signer_seeds = &[&seeds[..]][..];
 
-        let cpi_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            mint_ix,
-            signer_seeds,
-        );
-
-        token::mint_to(cpi_ctx, ctx.accounts.access_control.token_amount_for_pool)?;
-    }
+        let cpi_context = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
 
-    {
-        let transfer_wsol_ix = Transfer {
-            from: ctx.accounts.wsol_account.to_account_info(),
-            to: ctx.accounts.user_token_pc.to_account_info(),
-            authority: ctx.accounts.access_control.to_account_info(),
-        };
+        check_launch_price(&self.common.access_control, sol_amount, token_amount)?;
 
-        let bump = ctx.accounts.access_control.bump;
-        let seeds = &[
-            CONSTRAINT_SEED,
-            ctx.accounts.access_control.project_wallet.as_ref(),
-            &[bump],
-        ];
-        let signer_seeds = &[&seeds[..]][..];
+        initialize2(cpi_context, nonce, open_time, sol_amount, token_amount)?;
 
-        let cpi_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            transfer_wsol_ix,
-            signer_seeds,
-        );
+        self.accounts
+            .check_pool_balances(sol_amount, token_amount)?;
 
-        token::transfer(
-            cpi_ctx,
-            ctx.accounts.access_control.sol_amount_for_pool * 95 / 100,
-        )?;
+        Ok(LaunchedPool {
+            pool: self.accounts.amm.key(),
+            position: None,
+        })
     }
 
-    {
-        let transfer_wsol_ix = Transfer {
-            from: ctx.accounts.wsol_account.to_account_info(),
-            to: ctx.accounts.platform_wsol.to_account_info(),
-            authority: ctx.accounts.access_control.to_account_info(),
-        };
+    fn lp_accounts(&self) -> Option<LpAccounts<'info>> {
+        Some(LpAccounts {
+            lp_mint: self.accounts.lp_mint.to_account_info(),
+            user_lp_token_account: self.accounts.user_lp_token_account.to_account_info(),
+            platform_lp_associated_token: self
+                .accounts
+                .platform_lp_associated_token
+                .to_account_info(),
+        })
+    }
+}
 
-        let bump = ctx.accounts.access_control.bump;
-        let seeds = &[
-            CONSTRAINT_SEED,
-            ctx.accounts.access_control.project_wallet.as_ref(),
-            &[bump],
-        ];
-        let signer_seeds = &[&seeds[..]][..];
+impl<'info> UpdatePool<'info> {
+    fn launch_accounts(&self) -> LaunchAccounts<'info> {
+        LaunchAccounts {
+            access_control: self.access_control.clone(),
+            project_wallet: self.project_wallet.clone(),
+            wsol_account: self.wsol_account.clone(),
+            access_control_token_account: self.access_control_token_account.clone(),
+            coin_mint: self.coin_mint.clone(),
+            pc_mint: self.pc_mint.clone(),
+            token_program: self.token_program.clone(),
+            system_program: self.system_program.clone(),
+            associated_token_program: self.associated_token_program.clone(),
+            rent: self.rent.clone(),
+            platform: self.platform.clone(),
+            platform_owner: self.platform_owner.clone(),
+            platform_wsol: self.platform_wsol.clone(),
+            lp_vault: self.lp_vault.clone(),
+        }
+    }
 
-        let cpi_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            transfer_wsol_ix,
-            signer_seeds,
+    /// Checks the dex program and the market, then the pool accounts against the
+    /// addresses Raydium derives them at for that market.
+    fn check_pool_accounts(&self, nonce: u8) -> Result<()> {
+        let serum_program = self.serum_program.key();
+        require!(
+            serum_program
+                == Pubkey::from_str("srmqPvymJeFKQ4zGQed1GFppgkRHL9kaELCbyksJtPX").unwrap()
+                || serum_program
+                    == Pubkey::from_str("EoTcMgcDRTJVZDMZWBoU6rhYHKdzXk4oyQHNdf1rG9JS").unwrap(),
+            PoolError::InvalidSerumMarket
         );
 
-        token::transfer(
-            cpi_ctx,
-            ctx.accounts.access_control.sol_amount_for_pool
-                - ctx.accounts.access_control.sol_amount_for_pool * 95 / 100,
-        )?;
-    }
+        require!(
+            *self.serum_market.owner == serum_program,
+            PoolError::InvalidSerumMarket
+        );
 
-    {
-        let cpi_program = ctx.accounts.raydium_liquidity_pool_v4.to_account_info();
-        let cpi_accounts = Initialize2 {
-            raydium_liquidity_pool_v4: ctx.accounts.raydium_liquidity_pool_v4.to_account_info(),
-            token_program: ctx.accounts.token_program.clone(),
-            system_program: ctx.accounts.system_program.clone(),
-            spl_associated_token_account: ctx.accounts.associated_token_program.clone(),
-            rent: ctx.accounts.rent.clone(),
-            amm: ctx.accounts.amm.to_account_info(),
-            amm_authority: ctx.accounts.amm_authority.to_account_info(),
-            amm_open_orders: ctx.accounts.amm_open_orders.to_account_info(),
-            lp_mint: ctx.accounts.lp_mint.to_account_info(),
-            coin_mint: ctx.accounts.coin_mint.to_account_info(),
-            pc_mint: ctx.accounts.pc_mint.to_account_info(),
-            pool_coin_token_account: ctx.accounts.pool_coin_token_account.to_account_info(),
-            pool_pc_token_account: ctx.accounts.pool_pc_token_account.to_account_info(),
-            pool_withdraw_queue: ctx.accounts.pool_withdraw_queue.to_account_info(),
-            pool_target_orders: ctx.accounts.pool_target_orders.to_account_info(),
-            pool_temp_lp: ctx.accounts.pool_temp_lp.to_account_info(),
-            serum_program: ctx.accounts.serum_program.to_account_info(),
-            serum_market: ctx.accounts.serum_market.to_account_info(),
-            user_wallet: ctx.accounts.project_wallet.to_account_info(),
-            user_token_coin: ctx.accounts.user_token_coin.to_account_info(),
-            user_token_pc: ctx.accounts.user_token_pc.to_account_info(),
-            user_lp_token_account: ctx.accounts.user_lp_token_account.to_account_info(),
+        {
+            let data = self.serum_market.data.borrow();
+            require!(
+                data.len() >= SERUM_MARKET_PC_MINT_OFFSET + 32,
+                PoolError::InvalidSerumMarket
+            );
+
+            let market_mint = |offset: usize| Pubkey::try_from(&data[offset..offset + 32]).unwrap();
+            require!(
+                market_mint(SERUM_MARKET_COIN_MINT_OFFSET) == self.coin_mint.key()
+                    && market_mint(SERUM_MARKET_PC_MINT_OFFSET) == self.pc_mint.key(),
+                PoolError::InvalidSerumMarket
+            );
+        }
+
+        let program_id = self.raydium_liquidity_pool_v4.key();
+        let market = self.serum_market.key();
+        let associated = |seed: &[u8]| {
+            Pubkey::find_program_address(&[program_id.as_ref(), market.as_ref(), seed], &program_id)
+                .0
         };
 
-        let bump = ctx.accounts.access_control.bump;
-        let seeds = &[
-            CONSTRAINT_SEED,
-            ctx.accounts.project_wallet.key.as_ref(),
-            &[bump],
-        ];
-        let signer_seeds = &[&seeds[..]][..];
-
-        let cpi_context = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+        require!(
+            self.amm.key() == associated(AMM_ASSOCIATED_SEED)
+                && self.amm_open_orders.key() == associated(OPEN_ORDER_ASSOCIATED_SEED)
+                && self.lp_mint.key() == associated(LP_MINT_ASSOCIATED_SEED)
+                && self.pool_coin_token_account.key() == associated(COIN_VAULT_ASSOCIATED_SEED)
+                && self.pool_pc_token_account.key() == associated(PC_VAULT_ASSOCIATED_SEED)
+                && self.pool_withdraw_queue.key() == associated(TARGET_ASSOCIATED_SEED),
+            PoolError::InvalidRaydiumPoolAccount
+        );
 
-        initialize2(
-            cpi_context,
-            nonce,
-            open_time,
-            ctx.accounts.access_control.sol_amount_for_pool * 95 / 100,
-            ctx.accounts.access_control.token_amount_for_pool,
-        )?;
-    }
+        require!(
+            self.pool_target_orders.key()
+                == Pubkey::find_program_address(&[AMM_CONFIG_SEED], &program_id).0,
+            PoolError::InvalidRaydiumPoolAccount
+        );
 
-    {
-        let create_cpi = CpiContext::new(
-            ctx.accounts.associated_token_program.to_account_info(),
-            anchor_spl::associated_token::Create {
-                payer: ctx.accounts.project_wallet.to_account_info(),
-                associated_token: ctx.accounts.platform_lp_associated_token.to_account_info(),
-                authority: ctx.accounts.platform_owner.to_account_info(),
-                mint: ctx.accounts.lp_mint.to_account_info(),
-                system_program: ctx.accounts.system_program.to_account_info(),
-                token_program: ctx.accounts.token_program.to_account_info(),
-            },
+        let amm_authority =
+            Pubkey::create_program_address(&[AMM_AUTHORITY_SEED, &[nonce]], &program_id)
+                .map_err(|_| PoolError::InvalidRaydiumPoolAccount)?;
+        require!(
+            self.amm_authority.key() == amm_authority,
+            PoolError::InvalidRaydiumPoolAccount
         );
 
-        anchor_spl::associated_token::create(create_cpi)?;
+        Ok(())
+    }
 
-        // finish
+    /// Checks the vaults hold exactly the deposit and the project wallet got the LP,
+    /// Raydium keeps `10 ^ decimals` of the `sqrt(coin * pc)` initial liquidity locked.
+    fn check_pool_balances(&self, sol_amount: u64, token_amount: u64) -> Result<()> {
+        let coin_vault = TokenAccountStruct::unpack(&self.pool_coin_token_account.data.borrow())?;
+        let pc_vault = TokenAccountStruct::unpack(&self.pool_pc_token_account.data.borrow())?;
 
-        let amount =
-            TokenAccountStruct::unpack(&ctx.accounts.user_lp_token_account.data.borrow())?.amount;
+        require!(
+            coin_vault.amount == token_amount && pc_vault.amount == sol_amount,
+            PoolError::PoolBalanceMismatch
+        );
 
-        let transfer_token_ix = Transfer {
-            from: ctx.accounts.user_lp_token_account.to_account_info(),
-            to: ctx.accounts.platform_lp_associated_token.to_account_info(),
-            authority: ctx.accounts.project_wallet.to_account_info(),
-        };
+        let user_lp = TokenAccountStruct::unpack(&self.user_lp_token_account.data.borrow())?;
+        let liquidity = integer_sqrt(token_amount as u128 * sol_amount as u128);
 
-        let cpi_ctx = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            transfer_token_ix,
+        require!(
+            *self.user_lp_token_account.owner == token::ID
+                && user_lp.mint == self.lp_mint.key()
+                && user_lp.owner == self.project_wallet.key()
+                && user_lp.amount > 0
+                && user_lp.amount as u128 <= liquidity,
+            PoolError::PoolBalanceMismatch
         );
 
-        token::transfer(cpi_ctx, amount)?;
+        Ok(())
     }
+}
+
+/// Whether a pool funded with `token_pool_ratio` percent of the minted tokens and
+/// `sol_pool_ratio` percent of the donations, minus the 5% platform fee, prices within
+/// `LAUNCH_PRICE_TOLERANCE_BPS` of `init_mint_rate`.
+pub fn pool_ratios_match(token_pool_ratio: u8, sol_pool_ratio: u8) -> bool {
+    let token = token_pool_ratio as u64 * 100;
+    let sol = sol_pool_ratio as u64 * 95;
 
-    ctx.accounts.access_control.pool = Some(ctx.accounts.amm.to_account_info().key());
+    sol > 0 && token.abs_diff(sol) * 10_000 <= sol * LAUNCH_PRICE_TOLERANCE_BPS
+}
+
+/// The pool price in tokens per SOL must match the donation price `init_mint_rate`
+/// within `LAUNCH_PRICE_TOLERANCE_BPS`, so pool ratios have to account for the platform fee.
+/// Projects created before `init_project` checked the ratios with `pool_ratios_match` and
+/// failing it cannot launch, their donators get refunds once the launch deadline passes.
+pub fn check_launch_price(
+    access_control: &AccessControl,
+    sol_amount: u64,
+    token_amount: u64,
+) -> Result<()> {
+    require!(sol_amount > 0, PoolError::LaunchPriceMismatch);
+
+    let pool_rate = token_amount as u128 * (1e9 as u128) / sol_amount as u128;
+    let init_mint_rate = access_control.init_mint_rate as u128;
+
+    require!(
+        pool_rate.abs_diff(init_mint_rate) * 10_000
+            <= init_mint_rate * LAUNCH_PRICE_TOLERANCE_BPS as u128,
+        PoolError::LaunchPriceMismatch
+    );
 
     Ok(())
 }
@@ -596,9 +797,9 @@ pub fn initialize2_instruction(
     pc_mint: &Pubkey,
     pool_coin_token_account: &Pubkey,
     pool_pc_token_account: &Pubkey,
-    pool_withdraw_queue: &Pubkey,
-    pool_target_orders: &Pubkey,
-    pool_temp_lp: &Pubkey,
+    target_orders: &Pubkey,
+    amm_config: &Pubkey,
+    create_fee_destination: &Pubkey,
     serum_program: &Pubkey,
     serum_market: &Pubkey,
     user_wallet: &Pubkey,
@@ -635,9 +836,9 @@ pub fn initialize2_instruction(
     accounts.push(AccountMeta::new_readonly(*pc_mint, false));
     accounts.push(AccountMeta::new(*pool_coin_token_account, false));
     accounts.push(AccountMeta::new(*pool_pc_token_account, false));
-    accounts.push(AccountMeta::new(*pool_withdraw_queue, false));
-    accounts.push(AccountMeta::new_readonly(*pool_target_orders, false));
-    accounts.push(AccountMeta::new(*pool_temp_lp, false));
+    accounts.push(AccountMeta::new(*target_orders, false));
+    accounts.push(AccountMeta::new_readonly(*amm_config, false));
+    accounts.push(AccountMeta::new(*create_fee_destination, false));
     accounts.push(AccountMeta::new_readonly(*serum_program, false));
     accounts.push(AccountMeta::new_readonly(*serum_market, false)); // z
     accounts.push(AccountMeta::new(*user_wallet, true));
@@ -676,9 +877,9 @@ pub fn initialize2_signed<'info>(
         ctx.accounts.pc_mint.to_account_info().key,
         ctx.accounts.pool_coin_token_account.to_account_info().key,
         ctx.accounts.pool_pc_token_account.to_account_info().key,
-        ctx.accounts.pool_withdraw_queue.to_account_info().key,
-        ctx.accounts.pool_target_orders.to_account_info().key,
-        ctx.accounts.pool_temp_lp.to_account_info().key,
+        ctx.accounts.target_orders.to_account_info().key,
+        ctx.accounts.amm_config.to_account_info().key,
+        ctx.accounts.create_fee_destination.to_account_info().key,
         ctx.accounts.serum_program.to_account_info().key,
         ctx.accounts.serum_market.to_account_info().key,
         ctx.accounts.user_wallet.to_account_info().key,
@@ -707,9 +908,9 @@ pub fn initialize2_signed<'info>(
             ctx.accounts.pc_mint.to_account_info(),
             ctx.accounts.pool_coin_token_account.to_account_info(),
             ctx.accounts.pool_pc_token_account.to_account_info(),
-            ctx.accounts.pool_withdraw_queue.to_account_info(),
-            ctx.accounts.pool_target_orders.to_account_info(),
-            ctx.accounts.pool_temp_lp.to_account_info(),
+            ctx.accounts.target_orders.to_account_info(),
+            ctx.accounts.amm_config.to_account_info(),
+            ctx.accounts.create_fee_destination.to_account_info(),
             ctx.accounts.serum_program.to_account_info(),
             ctx.accounts.serum_market.to_account_info(),
             ctx.accounts.user_wallet.to_account_info(),
@@ -746,9 +947,9 @@ pub fn initialize2<'info>(
         ctx.accounts.pc_mint.to_account_info().key,
         ctx.accounts.pool_coin_token_account.to_account_info().key,
         ctx.accounts.pool_pc_token_account.to_account_info().key,
-        ctx.accounts.pool_withdraw_queue.to_account_info().key,
-        ctx.accounts.pool_target_orders.to_account_info().key,
-        ctx.accounts.pool_temp_lp.to_account_info().key,
+        ctx.accounts.target_orders.to_account_info().key,
+        ctx.accounts.amm_config.to_account_info().key,
+        ctx.accounts.create_fee_destination.to_account_info().key,
         ctx.accounts.serum_program.to_account_info().key,
         ctx.accounts.serum_market.to_account_info().key,
         ctx.accounts.user_wallet.to_account_info().key,
@@ -777,9 +978,9 @@ pub fn initialize2<'info>(
             ctx.accounts.pc_mint.to_account_info(),
             ctx.accounts.pool_coin_token_account.to_account_info(),
             ctx.accounts.pool_pc_token_account.to_account_info(),
-            ctx.accounts.pool_withdraw_queue.to_account_info(),
-            ctx.accounts.pool_target_orders.to_account_info(),
-            ctx.accounts.pool_temp_lp.to_account_info(),
+            ctx.accounts.target_orders.to_account_info(),
+            ctx.accounts.amm_config.to_account_info(),
+            ctx.accounts.create_fee_destination.to_account_info(),
             ctx.accounts.serum_program.to_account_info(),
             ctx.accounts.serum_market.to_account_info(),
             ctx.accounts.user_wallet.to_account_info(),
@@ -827,12 +1028,14 @@ pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
 
 #[derive(Accounts)]
 pub struct InitDonate<'info> {
-    #[account(init, payer = donator, seeds = [DONATE_SEED.as_ref(), access_control.key().as_ref(), donator.key().as_ref()], space = 8 + 1 + 32 + 8 + 8 + 32, bump)]
+    #[account(init, payer = donator, seeds = [DONATE_SEED, access_control.key().as_ref(), beneficiary.key().as_ref()], space = 8 + 1 + 32 + 8 + 8 + 32 + 8 * 6 + 1 + 1 + 1 + (1 + 32), bump)]
     pub donate_tracker: Account<'info, DonateTracker>,
     #[account(mut)]
     pub access_control: Account<'info, AccessControl>,
     #[account(mut)]
     pub donator: Signer<'info>,
+    /// CHECK: SAFE. Wallet the tracker is keyed on, may differ from the paying donator
+    pub beneficiary: AccountInfo<'info>,
     pub system_program: Program<'info, System>,
 }
 
@@ -845,7 +1048,7 @@ pub fn init_donate(ctx: Context<InitDonate>) -> Result<()> {
         &[
             DONATE_SEED,
             access_control.key().as_ref(),
-            ctx.accounts.donator.key().as_ref(),
+            ctx.accounts.beneficiary.key().as_ref(),
         ],
         ctx.program_id,
     );
@@ -853,24 +1056,65 @@ pub fn init_donate(ctx: Context<InitDonate>) -> Result<()> {
     donate_tracker.token_amount = 0;
     donate_tracker.donate_amount = 0;
     donate_tracker.access_control = access_control.key();
-    donate_tracker.donator = ctx.accounts.donator.key();
+    donate_tracker.donator = ctx.accounts.beneficiary.key();
+    donate_tracker.sol_amount_for_pool = 0;
+    donate_tracker.sol_amount_for_project = 0;
+    donate_tracker.token_amount_for_pool = 0;
+    donate_tracker.token_amount_for_project = 0;
+    donate_tracker.minted = 0;
+    donate_tracker.claimed_amount = 0;
+    donate_tracker.unreleased_refunded = false;
+    donate_tracker.expired = false;
+    donate_tracker.refunded = false;
+    donate_tracker.delegate = None;
 
     access_control.donator_amount += 1;
+
+    emit!(DonateTrackerInitialized {
+        access_control: access_control.key(),
+        donate_tracker: donate_tracker.key(),
+        donator: donate_tracker.donator,
+        payer: ctx.accounts.donator.key(),
+        donator_amount: access_control.donator_amount,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetDelegate<'info> {
+    #[account(mut, has_one = donator)]
+    pub donate_tracker: Account<'info, DonateTracker>,
+    pub donator: Signer<'info>,
+}
+
+pub fn set_delegate(ctx: Context<SetDelegate>, delegate: Option<Pubkey>) -> Result<()> {
+    ctx.accounts.donate_tracker.delegate = delegate;
+
+    emit!(DelegateChanged {
+        access_control: ctx.accounts.donate_tracker.access_control,
+        donate_tracker: ctx.accounts.donate_tracker.key(),
+        donator: ctx.accounts.donate_tracker.donator,
+        delegate,
+    });
+
     Ok(())
 }
 
 #[derive(Accounts)]
 pub struct Donate<'info> {
-    #[account(mut, seeds = [DONATE_SEED.as_ref(), access_control.key().as_ref(), donator.key().as_ref()], bump)]
+    #[account(mut, seeds = [DONATE_SEED, access_control.key().as_ref(), beneficiary.key().as_ref()], bump)]
     pub donate_tracker: Account<'info, DonateTracker>,
     #[account(mut)]
     pub access_control: Account<'info, AccessControl>,
     #[account(mut)]
     pub from: Account<'info, TokenAccount>,
-    #[account(mut)]
+    #[account(mut, constraint = to.key() == access_control.wsol_account @ DonateError::WsolAccountError)]
     pub to: Account<'info, TokenAccount>,
     #[account(mut)]
     pub donator: Signer<'info>,
+    /// CHECK: SAFE. Wallet credited with the donation, may differ from the paying donator
+    pub beneficiary: AccountInfo<'info>,
     #[account(address = token::ID)]
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
@@ -898,7 +1142,7 @@ pub fn donate(ctx: Context<Donate>, donate_amount: u64) -> Result<()> {
         DonateError::DonationNotOpen
     );
     require!(
-        access_control.donate_end as i64 >= current_time,
+        access_control.donate_end as i64 >= current_time && access_control.closed_at == 0,
         DonateError::DonationClosed
     );
 
@@ -912,28 +1156,36 @@ pub fn donate(ctx: Context<Donate>, donate_amount: u64) -> Result<()> {
         access_control.init_mint_rate as u128 * donate_amount as u128 / (1e9 as u128);
 
     require!(
-        ctx.accounts.donator.key() == donate_tracker.donator,
+        ctx.accounts.beneficiary.key() == donate_tracker.donator,
         DonateError::DonateAccountError
     );
     require!(
         access_control.key() == donate_tracker.access_control,
         DonateError::AccessControlAccountError
     );
-    donate_tracker.token_amount +=
-        (mint_token_amount as u128 * access_control.token_donator_ratio as u128 / 100) as u64;
+    let sol_amount_for_pool =
+        (donate_amount as u128 * access_control.sol_pool_ratio as u128 / 100) as u64;
+    let sol_amount_for_project =
+        (donate_amount as u128 * access_control.sol_project_ratio as u128 / 100) as u64;
+    let token_amount_for_donator =
+        mint_token_amount * access_control.token_donator_ratio as u128 / 100;
+    let token_amount_for_pool = mint_token_amount * access_control.token_pool_ratio as u128 / 100;
+    let token_amount_for_project =
+        mint_token_amount - token_amount_for_pool - token_amount_for_donator;
+
+    donate_tracker.token_amount += token_amount_for_donator as u64;
     donate_tracker.donate_amount += donate_amount;
+    donate_tracker.sol_amount_for_pool += sol_amount_for_pool;
+    donate_tracker.sol_amount_for_project += sol_amount_for_project;
+    donate_tracker.token_amount_for_pool += token_amount_for_pool as u64;
+    donate_tracker.token_amount_for_project += token_amount_for_project as u64;
+    donate_tracker.minted += mint_token_amount as u64;
 
     access_control.donate_amount += donate_amount;
-    access_control.sol_amount_for_pool +=
-        (donate_amount as u128 * access_control.sol_pool_ratio as u128 / 100) as u64;
-    access_control.sol_amount_for_project +=
-        (donate_amount as u128 * access_control.sol_project_ratio as u128 / 100) as u64;
-    access_control.token_amount_for_pool +=
-        (mint_token_amount as u128 * access_control.token_pool_ratio as u128 / 100) as u64;
-    access_control.token_amount_for_project += (mint_token_amount
-        - mint_token_amount as u128 * access_control.token_pool_ratio as u128 / 100
-        - mint_token_amount as u128 * access_control.token_donator_ratio as u128 / 100)
-        as u64;
+    access_control.sol_amount_for_pool += sol_amount_for_pool;
+    access_control.sol_amount_for_project += sol_amount_for_project;
+    access_control.token_amount_for_pool += token_amount_for_pool as u64;
+    access_control.token_amount_for_project += token_amount_for_project as u64;
     access_control.minted += mint_token_amount as u64;
 
     require!(
@@ -941,9 +1193,218 @@ pub fn donate(ctx: Context<Donate>, donate_amount: u64) -> Result<()> {
         DonateError::DonationAmountMax
     );
 
+    emit!(Donated {
+        access_control: access_control.key(),
+        donate_tracker: donate_tracker.key(),
+        payer: ctx.accounts.donator.key(),
+        donator: donate_tracker.donator,
+        amount: donate_amount,
+        tracker_donate_amount: donate_tracker.donate_amount,
+        tracker_token_amount: donate_tracker.token_amount,
+        donate_amount: access_control.donate_amount,
+        sol_amount_for_pool: access_control.sol_amount_for_pool,
+        sol_amount_for_project: access_control.sol_amount_for_project,
+        token_amount_for_pool: access_control.token_amount_for_pool,
+        token_amount_for_project: access_control.token_amount_for_project,
+        minted: access_control.minted,
+    });
+
+    if access_control.donate_amount == access_control.donate_amount_max {
+        // hard cap reached, the project succeeded and can launch right away
+        access_control.closed_at = current_time as u32;
+
+        emit!(DonationClosed {
+            access_control: access_control.key(),
+            closed_at: access_control.closed_at,
+            donate_amount: access_control.donate_amount,
+        });
+    } else if access_control.anti_snipe_window > 0
+        && current_time + access_control.anti_snipe_window as i64
+            >= access_control.donate_end as i64
+    {
+        // anti-snipe: a donation close to the end pushes donate_end back, up to the cap
+        let donate_end = access_control.donate_end.saturating_add(
+            access_control
+                .anti_snipe_extension
+                .min(access_control.anti_snipe_extension_max - access_control.donate_end_extended),
+        );
+        let extension = donate_end - access_control.donate_end;
+
+        if extension > 0 {
+            access_control.donate_end = donate_end;
+            access_control.donate_end_extended += extension;
+
+            emit!(DonateEndExtended {
+                access_control: access_control.key(),
+                donate_end: access_control.donate_end,
+                donate_end_extended: access_control.donate_end_extended,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawDonation<'info> {
+    #[account(mut, has_one = wsol_account)]
+    pub access_control: Account<'info, AccessControl>,
+    #[account(mut, has_one = donator, has_one = access_control)]
+    pub donate_tracker: Account<'info, DonateTracker>,
+    pub donator: Signer<'info>,
+    #[account(mut)]
+    pub wsol_account: Account<'info, TokenAccount>,
+    #[account(mut, constraint = to.owner == donate_tracker.donator @ WithdrawError::AccountError)]
+    pub to: Account<'info, TokenAccount>,
+    #[account(seeds = [PLATFORM_SEED], bump)]
+    pub platform: Account<'info, OwnerAccount>,
+    /// CHECK: SAFE. Project or platform wsol account receiving the penalty
+    #[account(mut)]
+    pub penalty_to: Account<'info, TokenAccount>,
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn withdraw_donation(ctx: Context<WithdrawDonation>, amount: u64) -> Result<()> {
+    let current_time = Clock::get()?.unix_timestamp;
+
+    require!(
+        ctx.accounts.access_control.donate_end as i64 >= current_time
+            && ctx.accounts.access_control.closed_at == 0,
+        WithdrawError::DonationClosed
+    );
+
+    require!(
+        ctx.accounts.access_control.pool.is_none(),
+        WithdrawError::PoolAlreadyInitialized
+    );
+
+    require!(
+        amount > 0 && amount <= ctx.accounts.donate_tracker.donate_amount,
+        WithdrawError::AmountError
+    );
+
+    require!(
+        ctx.accounts.to.mint == spl_token::native_mint::id(),
+        WithdrawError::AccountError
+    );
+
+    if ctx.accounts.access_control.withdraw_penalty_to_platform {
+        require!(
+            ctx.accounts.penalty_to.key() == ctx.accounts.platform.platform_wsol,
+            WithdrawError::AccountError
+        );
+    } else {
+        require!(
+            ctx.accounts.penalty_to.owner == ctx.accounts.access_control.project_wallet
+                && ctx.accounts.penalty_to.mint == spl_token::native_mint::id(),
+            WithdrawError::AccountError
+        );
+    }
+
+    let penalty =
+        (amount as u128 * ctx.accounts.access_control.withdraw_penalty_ratio as u128 / 100) as u64;
+
+    let bump = ctx.accounts.access_control.bump;
+    let seeds = &[
+        CONSTRAINT_SEED,
+        ctx.accounts.access_control.project_wallet.as_ref(),
+        &[bump],
+    ];
+    let signer_seeds = &[&seeds[..]][..];
+
+    let transfer_ix = Transfer {
+        from: ctx.accounts.wsol_account.to_account_info(),
+        to: ctx.accounts.to.to_account_info(),
+        authority: ctx.accounts.access_control.to_account_info(),
+    };
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        transfer_ix,
+        signer_seeds,
+    );
+
+    token::transfer(cpi_ctx, amount - penalty)?;
+
+    if penalty > 0 {
+        let transfer_ix = Transfer {
+            from: ctx.accounts.wsol_account.to_account_info(),
+            to: ctx.accounts.penalty_to.to_account_info(),
+            authority: ctx.accounts.access_control.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_ix,
+            signer_seeds,
+        );
+
+        token::transfer(cpi_ctx, penalty)?;
+    }
+
+    revert_donation(
+        &mut ctx.accounts.access_control,
+        &mut ctx.accounts.donate_tracker,
+        amount,
+    );
+
+    let access_control = &ctx.accounts.access_control;
+    let donate_tracker = &ctx.accounts.donate_tracker;
+    emit!(DonationWithdrawn {
+        access_control: access_control.key(),
+        donate_tracker: donate_tracker.key(),
+        donator: donate_tracker.donator,
+        amount,
+        penalty,
+        tracker_donate_amount: donate_tracker.donate_amount,
+        tracker_token_amount: donate_tracker.token_amount,
+        donate_amount: access_control.donate_amount,
+        sol_amount_for_pool: access_control.sol_amount_for_pool,
+        sol_amount_for_project: access_control.sol_amount_for_project,
+        token_amount_for_pool: access_control.token_amount_for_pool,
+        token_amount_for_project: access_control.token_amount_for_project,
+        minted: access_control.minted,
+    });
+
     Ok(())
 }
 
+/// Removes `amount` of the tracker's donation from the tracker and the project
+/// aggregates. Every share is taken pro-rata from what the tracker recorded at
+/// donation time, so the aggregates always equal the sum over all trackers. The
+/// reverted `minted` is the sum of the reverted token parts, so it keeps covering them.
+pub fn revert_donation(
+    access_control: &mut AccessControl,
+    donate_tracker: &mut DonateTracker,
+    amount: u64,
+) {
+    let donate_amount = donate_tracker.donate_amount;
+    let share = |value: u64| (value as u128 * amount as u128 / donate_amount as u128) as u64;
+
+    let token_amount = share(donate_tracker.token_amount);
+    let sol_amount_for_pool = share(donate_tracker.sol_amount_for_pool);
+    let sol_amount_for_project = share(donate_tracker.sol_amount_for_project);
+    let token_amount_for_pool = share(donate_tracker.token_amount_for_pool);
+    let token_amount_for_project = share(donate_tracker.token_amount_for_project);
+    let minted = token_amount + token_amount_for_pool + token_amount_for_project;
+
+    donate_tracker.donate_amount -= amount;
+    donate_tracker.token_amount -= token_amount;
+    donate_tracker.sol_amount_for_pool -= sol_amount_for_pool;
+    donate_tracker.sol_amount_for_project -= sol_amount_for_project;
+    donate_tracker.token_amount_for_pool -= token_amount_for_pool;
+    donate_tracker.token_amount_for_project -= token_amount_for_project;
+    donate_tracker.minted -= minted;
+
+    access_control.donate_amount -= amount;
+    access_control.sol_amount_for_pool -= sol_amount_for_pool;
+    access_control.sol_amount_for_project -= sol_amount_for_project;
+    access_control.token_amount_for_pool -= token_amount_for_pool;
+    access_control.token_amount_for_project -= token_amount_for_project;
+    access_control.minted -= minted;
+}
+
 #[error_code]
 pub enum DonateError {
     #[msg("Donation is closed")]
@@ -958,6 +1419,20 @@ pub enum DonateError {
     DonateAccountError,
     #[msg("AccessControl account is error")]
     AccessControlAccountError,
+    #[msg("Donation must go to the project wSOL account")]
+    WsolAccountError,
+}
+
+#[error_code]
+pub enum WithdrawError {
+    #[msg("Donation is closed")]
+    DonationClosed,
+    #[msg("Pool is already initialized")]
+    PoolAlreadyInitialized,
+    #[msg("Withdraw amount incorrect")]
+    AmountError,
+    #[msg("Account incorrect")]
+    AccountError,
 }
 
 #[error_code]
@@ -966,12 +1441,32 @@ pub enum InitProjectError {
     MintAuthorityError,
     #[msg("Token mint incorrect")]
     MintError,
+    #[msg("Token mint must have no supply")]
+    MintSupplyError,
+    #[msg("Freeze authority must be unset or the program hosted account")]
+    FreezeAuthorityError,
     #[msg("Token owenr must be Access Control")]
     TokenOwnerError,
     #[msg("Token program not correct")]
     AccountProgramError,
     #[msg("Ratio Incorrect")]
     RatioError,
+    #[msg("Withdraw penalty ratio incorrect")]
+    WithdrawPenaltyRatioError,
+    #[msg("Vesting schedule incorrect")]
+    VestingError,
+    #[msg("Milestones incorrect")]
+    MilestoneError,
+    #[msg("Launch window outside platform bounds")]
+    LaunchWindowError,
+    #[msg("Launch venue parameters incorrect")]
+    LaunchVenueError,
+    #[msg("LP policy incorrect for the launch venue")]
+    LpPolicyError,
+    #[msg("Claim period shorter than the vesting schedule")]
+    ClaimPeriodError,
+    #[msg("Pool ratios do not price the pool at the donation price")]
+    PoolRatioError,
 }
 
 #[error_code]
@@ -984,4 +1479,258 @@ pub enum PoolError {
     DonationNotSatisfied,
     #[msg("Account incorrect")]
     AccountError,
+    #[msg("Freeze authority must be unset or the program hosted account")]
+    FreezeAuthorityError,
+    #[msg("Project launches on another venue")]
+    LaunchVenueError,
+    #[msg("Invalid Raydium CP-Swap account")]
+    InvalidRaydiumCpmmAccount,
+    #[msg("Invalid Orca whirlpool account")]
+    InvalidWhirlpoolAccount,
+    #[msg("Launch price out of the pool price range")]
+    PriceOutOfRange,
+    #[msg("Invalid Meteora DLMM account")]
+    InvalidDlmmAccount,
+    #[msg("Raydium pool account does not match its derived address")]
+    InvalidRaydiumPoolAccount,
+    #[msg("Invalid serum market")]
+    InvalidSerumMarket,
+    #[msg("Pool price does not match the donation price")]
+    LaunchPriceMismatch,
+    #[msg("Pool balances do not match the deposit")]
+    PoolBalanceMismatch,
+    #[msg("Pool config account does not match its derived address")]
+    InvalidPoolConfig,
+}
+
+#[error_code]
+pub enum PlatformError {
+    #[msg("Launch window minimum above the maximum")]
+    LaunchWindowError,
+    #[msg("Platform account already migrated")]
+    AlreadyMigrated,
+    #[msg("Signer is not the platform owner")]
+    Unauthorized,
+    #[msg("Account already migrated")]
+    AccountAlreadyMigrated,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeSet;
+
+    fn account_info(
+        key: Pubkey,
+        owner: Pubkey,
+        data: Vec<u8>,
+        is_signer: bool,
+        executable: bool,
+    ) -> AccountInfo<'static> {
+        AccountInfo::new(
+            Box::leak(Box::new(key)),
+            is_signer,
+            true,
+            Box::leak(Box::new(1_000_000_000)),
+            Box::leak(data.into_boxed_slice()),
+            Box::leak(Box::new(owner)),
+            executable,
+            0,
+        )
+    }
+
+    fn token_account_info(key: Pubkey, owner: Pubkey) -> AccountInfo<'static> {
+        let mut data = vec![0; TokenAccountStruct::LEN];
+        TokenAccountStruct::pack(
+            TokenAccountStruct {
+                mint: spl_token::native_mint::id(),
+                owner,
+                state: spl_token::state::AccountState::Initialized,
+                ..Default::default()
+            },
+            &mut data,
+        )
+        .unwrap();
+        account_info(key, token::ID, data, false, false)
+    }
+
+    fn program_account_info<T: AccountSerialize>(key: Pubkey, account: &T) -> AccountInfo<'static> {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        account_info(key, crate::ID, data, false, false)
+    }
+
+    /// Runs the `Donate` account checks, paying into the project wSOL account or into
+    /// another wSOL account of the access control.
+    fn donate_accounts(into_wsol_account: bool) -> Result<()> {
+        let access_control = Pubkey::new_unique();
+        let wsol_account = Pubkey::new_unique();
+        let donator = Pubkey::new_unique();
+        let donate_tracker = Pubkey::find_program_address(
+            &[DONATE_SEED, access_control.as_ref(), donator.as_ref()],
+            &crate::ID,
+        )
+        .0;
+
+        let accounts: &'static [AccountInfo<'static>] = Box::leak(Box::new([
+            program_account_info(
+                donate_tracker,
+                &DonateTracker {
+                    access_control,
+                    donator,
+                    ..Default::default()
+                },
+            ),
+            program_account_info(
+                access_control,
+                &AccessControl {
+                    wsol_account,
+                    ..Default::default()
+                },
+            ),
+            token_account_info(Pubkey::new_unique(), donator),
+            token_account_info(
+                if into_wsol_account {
+                    wsol_account
+                } else {
+                    Pubkey::new_unique()
+                },
+                access_control,
+            ),
+            account_info(donator, System::id(), vec![], true, false),
+            account_info(donator, System::id(), vec![], false, false),
+            account_info(Token::id(), Pubkey::default(), vec![], false, true),
+            account_info(System::id(), Pubkey::default(), vec![], false, true),
+        ]));
+
+        Donate::try_accounts(
+            &crate::ID,
+            &mut &accounts[..],
+            &[],
+            &mut DonateBumps::default(),
+            &mut BTreeSet::new(),
+        )
+        .map(|_| ())
+    }
+
+    #[test]
+    fn donation_into_the_project_wsol_account() {
+        assert!(donate_accounts(true).is_ok());
+    }
+
+    #[test]
+    fn donation_into_a_foreign_account() {
+        assert_eq!(
+            donate_accounts(false),
+            Err(DonateError::WsolAccountError.into())
+        );
+    }
+
+    fn access_control(init_mint_rate: u64) -> AccessControl {
+        AccessControl {
+            init_mint_rate,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn reverted_donation_keeps_minted_the_sum_of_its_parts() {
+        let mut access_control = AccessControl {
+            donate_amount: 11,
+            token_amount_for_pool: 4,
+            token_amount_for_project: 3,
+            minted: 11,
+            ..Default::default()
+        };
+        let mut donate_tracker = DonateTracker {
+            donate_amount: 11,
+            token_amount: 4,
+            token_amount_for_pool: 4,
+            token_amount_for_project: 3,
+            minted: 11,
+            ..Default::default()
+        };
+
+        revert_donation(&mut access_control, &mut donate_tracker, 3);
+
+        assert_eq!(donate_tracker.donate_amount, 8);
+        assert_eq!(
+            donate_tracker.minted,
+            donate_tracker.token_amount
+                + donate_tracker.token_amount_for_pool
+                + donate_tracker.token_amount_for_project
+        );
+        assert_eq!(
+            access_control.minted,
+            donate_tracker.token_amount
+                + access_control.token_amount_for_pool
+                + access_control.token_amount_for_project
+        );
+
+        revert_donation(&mut access_control, &mut donate_tracker, 8);
+
+        assert_eq!(donate_tracker.donate_amount, 0);
+        assert_eq!(donate_tracker.minted, 0);
+        assert_eq!(access_control.minted, 0);
+        assert_eq!(access_control.token_amount_for_pool, 0);
+        assert_eq!(access_control.token_amount_for_project, 0);
+    }
+
+    #[test]
+    fn launch_price_within_tolerance() {
+        let access_control = access_control(1_000_000);
+        assert!(check_launch_price(&access_control, 1_000_000_000, 1_000_000).is_ok());
+        assert!(check_launch_price(&access_control, 1_000_000_000, 1_010_000).is_ok());
+        assert!(check_launch_price(&access_control, 1_000_000_000, 990_000).is_ok());
+    }
+
+    #[test]
+    fn launch_price_out_of_tolerance() {
+        let access_control = access_control(1_000_000);
+        assert!(check_launch_price(&access_control, 1_000_000_000, 1_010_001).is_err());
+        assert!(check_launch_price(&access_control, 1_000_000_000, 989_999).is_err());
+    }
+
+    #[test]
+    fn launch_price_without_sol() {
+        assert!(check_launch_price(&access_control(1_000_000), 0, 1_000_000).is_err());
+    }
+
+    #[test]
+    fn launch_price_with_large_amounts() {
+        assert!(check_launch_price(&access_control(u64::MAX), 1_000_000_000, u64::MAX).is_ok());
+        assert!(check_launch_price(&access_control(1), u64::MAX, u64::MAX).is_err());
+    }
+
+    #[test]
+    fn pool_ratios_covering_the_platform_fee() {
+        assert!(pool_ratios_match(19, 20));
+        assert!(pool_ratios_match(38, 40));
+        assert!(pool_ratios_match(95, 100));
+        assert!(pool_ratios_match(47, 49));
+    }
+
+    #[test]
+    fn pool_ratios_off_the_donation_price() {
+        assert!(!pool_ratios_match(20, 20));
+        assert!(!pool_ratios_match(47, 50));
+        assert!(!pool_ratios_match(48, 50));
+        assert!(!pool_ratios_match(100, 0));
+    }
+
+    #[test]
+    fn donations_at_matching_ratios_pass_the_launch_price() {
+        let init_mint_rate = 1_000_000;
+        let donate_amount = 123_456_789_000u64;
+        let (token_pool_ratio, sol_pool_ratio) = (38, 40);
+
+        let minted = init_mint_rate as u128 * donate_amount as u128 / 1_000_000_000;
+        let token_amount = (minted * token_pool_ratio as u128 / 100) as u64;
+        let sol_amount = donate_amount * sol_pool_ratio / 100 * 95 / 100;
+
+        assert!(
+            check_launch_price(&access_control(init_mint_rate), sol_amount, token_amount).is_ok()
+        );
+    }
 }
@@ -0,0 +1,332 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        self,
+        instruction::{AccountMeta, Instruction},
+    },
+    Result,
+};
+use anchor_spl::token::TokenAccount;
+
+use std::str::FromStr;
+
+use crate::access::launch::*;
+use crate::access::owner::PoolError;
+use crate::access::types::{LaunchVenue, CONSTRAINT_SEED};
+
+/// Anchor discriminator of the CP-Swap `initialize` instruction.
+pub const CPMM_INITIALIZE_DISCRIMINATOR: [u8; 8] = [175, 175, 109, 31, 13, 152, 155, 237];
+
+#[derive(Accounts)]
+pub struct UpdatePoolCpmm<'info> {
+    pub common: LaunchAccounts<'info>,
+
+    /// CHECK: Safe. Raydium CP-Swap program
+    pub raydium_cpmm: AccountInfo<'info>,
+    /// CHECK: Safe. Amm config of the default fee tier, a PDA create with seed = [b"amm_config", 0u16.to_be_bytes()]
+    pub amm_config: AccountInfo<'info>,
+    /// CHECK: Safe. Vault and lp mint authority, a PDA create with seed = [b"vault_and_lp_mint_auth_seed"]
+    pub amm_authority: AccountInfo<'info>,
+    /// CHECK: Safe. Pool state account
+    #[account(mut)]
+    pub pool_state: AccountInfo<'info>,
+    /// CHECK: Safe. Lp mint account
+    #[account(mut)]
+    pub lp_mint: AccountInfo<'info>,
+    /// CHECK: Safe. Pool coin vault, created by the program
+    #[account(mut)]
+    pub coin_vault: AccountInfo<'info>,
+    /// CHECK: Safe. Pool pc vault, created by the program
+    #[account(mut)]
+    pub pc_vault: AccountInfo<'info>,
+    /// CHECK: Safe. Create pool fee destination
+    #[account(mut)]
+    pub create_pool_fee: AccountInfo<'info>,
+    /// CHECK: Safe. Oracle observation account
+    #[account(mut)]
+    pub observation_state: AccountInfo<'info>,
+    #[account(mut)]
+    pub user_token_coin: Box<Account<'info, TokenAccount>>,
+    /// CHECK: Safe. User pc token account to deposit into.
+    #[account(mut)]
+    pub user_token_pc: Box<Account<'info, TokenAccount>>,
+    /// CHECK: Safe. User lp token ATA, created by the program
+    #[account(mut)]
+    pub user_lp_token_account: AccountInfo<'info>,
+
+    #[account(mut)]
+    /// CHECK: Safe. Platform associated token account, the LP vault one when the LP is locked
+    pub platform_lp_associated_token: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CpmmInitialize<'info> {
+    /// CHECK: Safe. Raydium CP-Swap program
+    pub raydium_cpmm: AccountInfo<'info>,
+    /// CHECK: Safe. The user wallet create the pool
+    pub creator: AccountInfo<'info>,
+    /// CHECK: Safe. Amm config
+    pub amm_config: AccountInfo<'info>,
+    /// CHECK: Safe. Vault and lp mint authority
+    pub authority: AccountInfo<'info>,
+    /// CHECK: Safe. Pool state account
+    pub pool_state: AccountInfo<'info>,
+    /// CHECK: Safe. Token 0 mint, the smaller mint address
+    pub token_0_mint: AccountInfo<'info>,
+    /// CHECK: Safe. Token 1 mint
+    pub token_1_mint: AccountInfo<'info>,
+    /// CHECK: Safe. Lp mint account
+    pub lp_mint: AccountInfo<'info>,
+    /// CHECK: Safe. User token 0 account
+    pub creator_token_0: AccountInfo<'info>,
+    /// CHECK: Safe. User token 1 account
+    pub creator_token_1: AccountInfo<'info>,
+    /// CHECK: Safe. User lp token ATA
+    pub creator_lp_token: AccountInfo<'info>,
+    /// CHECK: Safe. Token 0 vault
+    pub token_0_vault: AccountInfo<'info>,
+    /// CHECK: Safe. Token 1 vault
+    pub token_1_vault: AccountInfo<'info>,
+    /// CHECK: Safe. Create pool fee destination
+    pub create_pool_fee: AccountInfo<'info>,
+    /// CHECK: Safe. Oracle observation account
+    pub observation_state: AccountInfo<'info>,
+    /// CHECK: Safe. Token program
+    pub token_program: AccountInfo<'info>,
+    /// CHECK: Safe. Associated token program
+    pub associated_token_program: AccountInfo<'info>,
+    /// CHECK: Safe. System program
+    pub system_program: AccountInfo<'info>,
+    /// CHECK: Safe. Rent sysvar
+    pub rent: AccountInfo<'info>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, AnchorSerialize, AnchorDeserialize)]
+pub struct CpmmInitializeInstruction {
+    /// init token 0 amount
+    pub init_amount_0: u64,
+    /// init token 1 amount
+    pub init_amount_1: u64,
+    /// utc timestamps for pool open
+    pub open_time: u64,
+}
+
+/// Launches into a Raydium CP-Swap pool, which needs no OpenBook market.
+pub fn update_pool_cpmm(ctx: Context<UpdatePoolCpmm>, open_time: u64) -> Result<()> {
+    launch(ctx.accounts, open_time)
+}
+
+impl<'info> LaunchAdapter<'info> for UpdatePoolCpmm<'info> {
+    const VENUE: LaunchVenue = LaunchVenue::RaydiumCpmm;
+    /// Pool open time.
+    type Args = u64;
+
+    fn common(&self) -> &LaunchAccounts<'info> {
+        &self.common
+    }
+
+    fn common_mut(&mut self) -> &mut LaunchAccounts<'info> {
+        &mut self.common
+    }
+
+    fn validate(&self, _args: &Self::Args) -> Result<()> {
+        require!(
+            self.raydium_cpmm.key()
+                == Pubkey::from_str("CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C").unwrap()
+                || self.raydium_cpmm.key()
+                    == Pubkey::from_str("CPMDWBwJDtYax9qW7AyRuVC19Cc4L4Vcy4n2BHAbHkCW").unwrap(),
+            PoolError::InvalidRaydiumCpmmAccount
+        );
+
+        let (amm_config, _) = Pubkey::find_program_address(
+            &[b"amm_config", &0u16.to_be_bytes()],
+            &self.raydium_cpmm.key(),
+        );
+        require!(
+            self.amm_config.key() == amm_config,
+            PoolError::InvalidPoolConfig
+        );
+
+        check_user_token_accounts(&self.common, &self.user_token_coin, &self.user_token_pc)
+    }
+
+    fn funding_accounts(&self) -> (AccountInfo<'info>, AccountInfo<'info>) {
+        (
+            self.user_token_coin.to_account_info(),
+            self.user_token_pc.to_account_info(),
+        )
+    }
+
+    fn create_pool(
+        &self,
+        open_time: Self::Args,
+        sol_amount: u64,
+        token_amount: u64,
+    ) -> Result<LaunchedPool> {
+        // CP-Swap orders the pair by mint address
+        let coin_is_token_0 = self.common.coin_mint.key() < self.common.pc_mint.key();
+        let (
+            token_0_mint,
+            token_1_mint,
+            creator_token_0,
+            creator_token_1,
+            token_0_vault,
+            token_1_vault,
+            init_amount_0,
+            init_amount_1,
+        ) = if coin_is_token_0 {
+            (
+                self.common.coin_mint.to_account_info(),
+                self.common.pc_mint.to_account_info(),
+                self.user_token_coin.to_account_info(),
+                self.user_token_pc.to_account_info(),
+                self.coin_vault.to_account_info(),
+                self.pc_vault.to_account_info(),
+                token_amount,
+                sol_amount,
+            )
+        } else {
+            (
+                self.common.pc_mint.to_account_info(),
+                self.common.coin_mint.to_account_info(),
+                self.user_token_pc.to_account_info(),
+                self.user_token_coin.to_account_info(),
+                self.pc_vault.to_account_info(),
+                self.coin_vault.to_account_info(),
+                sol_amount,
+                token_amount,
+            )
+        };
+
+        let cpi_program = self.raydium_cpmm.to_account_info();
+        let cpi_accounts = CpmmInitialize {
+            raydium_cpmm: self.raydium_cpmm.to_account_info(),
+            creator: self.common.project_wallet.to_account_info(),
+            amm_config: self.amm_config.to_account_info(),
+            authority: self.amm_authority.to_account_info(),
+            pool_state: self.pool_state.to_account_info(),
+            token_0_mint,
+            token_1_mint,
+            lp_mint: self.lp_mint.to_account_info(),
+            creator_token_0,
+            creator_token_1,
+            creator_lp_token: self.user_lp_token_account.to_account_info(),
+            token_0_vault,
+            token_1_vault,
+            create_pool_fee: self.create_pool_fee.to_account_info(),
+            observation_state: self.observation_state.to_account_info(),
+            token_program: self.common.token_program.to_account_info(),
+            associated_token_program: self.common.associated_token_program.to_account_info(),
+            system_program: self.common.system_program.to_account_info(),
+            rent: self.common.rent.to_account_info(),
+        };
+
+        let bump = self.common.access_control.bump;
+        let seeds = &[
+            CONSTRAINT_SEED,
+            self.common.project_wallet.key.as_ref(),
+            &[bump],
+        ];
+        let signer_seeds = &[&seeds[..]][..];
+
+        let cpi_context = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+        cpmm_initialize(cpi_context, init_amount_0, init_amount_1, open_time)?;
+
+        Ok(LaunchedPool {
+            pool: self.pool_state.key(),
+            position: None,
+        })
+    }
+
+    fn lp_accounts(&self) -> Option<LpAccounts<'info>> {
+        Some(LpAccounts {
+            lp_mint: self.lp_mint.to_account_info(),
+            user_lp_token_account: self.user_lp_token_account.to_account_info(),
+            platform_lp_associated_token: self.platform_lp_associated_token.to_account_info(),
+        })
+    }
+}
+
+pub fn cpmm_initialize_instruction(
+    accounts: &CpmmInitialize,
+    init_amount_0: u64,
+    init_amount_1: u64,
+    open_time: u64,
+) -> Result<Instruction> {
+    let mut data = CPMM_INITIALIZE_DISCRIMINATOR.to_vec();
+    data.extend(
+        CpmmInitializeInstruction {
+            init_amount_0,
+            init_amount_1,
+            open_time,
+        }
+        .try_to_vec()?,
+    );
+
+    let metas = vec![
+        AccountMeta::new(*accounts.creator.key, true),
+        AccountMeta::new_readonly(*accounts.amm_config.key, false),
+        AccountMeta::new_readonly(*accounts.authority.key, false),
+        AccountMeta::new(*accounts.pool_state.key, false),
+        AccountMeta::new_readonly(*accounts.token_0_mint.key, false),
+        AccountMeta::new_readonly(*accounts.token_1_mint.key, false),
+        AccountMeta::new(*accounts.lp_mint.key, false),
+        AccountMeta::new(*accounts.creator_token_0.key, false),
+        AccountMeta::new(*accounts.creator_token_1.key, false),
+        AccountMeta::new(*accounts.creator_lp_token.key, false),
+        AccountMeta::new(*accounts.token_0_vault.key, false),
+        AccountMeta::new(*accounts.token_1_vault.key, false),
+        AccountMeta::new(*accounts.create_pool_fee.key, false),
+        AccountMeta::new(*accounts.observation_state.key, false),
+        AccountMeta::new_readonly(*accounts.token_program.key, false),
+        // token 0 and token 1 programs, both legacy spl token
+        AccountMeta::new_readonly(*accounts.token_program.key, false),
+        AccountMeta::new_readonly(*accounts.token_program.key, false),
+        AccountMeta::new_readonly(*accounts.associated_token_program.key, false),
+        AccountMeta::new_readonly(*accounts.system_program.key, false),
+        AccountMeta::new_readonly(*accounts.rent.key, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *accounts.raydium_cpmm.key,
+        accounts: metas,
+        data,
+    })
+}
+
+pub fn cpmm_initialize<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, CpmmInitialize<'info>>,
+    init_amount_0: u64,
+    init_amount_1: u64,
+    open_time: u64,
+) -> Result<()> {
+    let ix = cpmm_initialize_instruction(&ctx.accounts, init_amount_0, init_amount_1, open_time)?;
+
+    solana_program::program::invoke_signed(
+        &ix,
+        &[
+            ctx.accounts.raydium_cpmm.clone(),
+            ctx.accounts.creator.clone(),
+            ctx.accounts.amm_config.clone(),
+            ctx.accounts.authority.clone(),
+            ctx.accounts.pool_state.clone(),
+            ctx.accounts.token_0_mint.clone(),
+            ctx.accounts.token_1_mint.clone(),
+            ctx.accounts.lp_mint.clone(),
+            ctx.accounts.creator_token_0.clone(),
+            ctx.accounts.creator_token_1.clone(),
+            ctx.accounts.creator_lp_token.clone(),
+            ctx.accounts.token_0_vault.clone(),
+            ctx.accounts.token_1_vault.clone(),
+            ctx.accounts.create_pool_fee.clone(),
+            ctx.accounts.observation_state.clone(),
+            ctx.accounts.token_program.clone(),
+            ctx.accounts.associated_token_program.clone(),
+            ctx.accounts.system_program.clone(),
+            ctx.accounts.rent.clone(),
+        ],
+        ctx.signer_seeds,
+    )
+    .map_err(Into::into)
+}
@@ -3,6 +3,11 @@ use anchor_lang::prelude::*;
 pub const CONSTRAINT_SEED: &[u8] = b"project";
 pub const DONATE_SEED: &[u8] = b"donate";
 pub const PLATFORM_SEED: &[u8] = b"platform";
+pub const PROPOSAL_SEED: &[u8] = b"proposal";
+pub const VOTE_SEED: &[u8] = b"vote";
+pub const LP_VAULT_SEED: &[u8] = b"lp_vault";
+
+pub const MAX_MILESTONES: usize = 5;
 
 #[account]
 #[derive(Default)]
@@ -35,6 +40,113 @@ pub struct AccessControl {
     pub access_control_token_account: Pubkey,
 
     pub donator_amount: u64,
+
+    pub withdraw_penalty_ratio: u8,
+    pub withdraw_penalty_to_platform: bool,
+
+    pub anti_snipe_window: u32,
+    pub anti_snipe_extension: u32,
+    pub anti_snipe_extension_max: u32,
+    pub donate_end_extended: u32,
+
+    pub closed_at: u32,
+
+    pub vesting_tge_ratio: u8,
+    pub vesting_cliff: u32,
+    pub vesting_duration: u32,
+    pub launched_at: u32,
+
+    pub project_vesting_cliff: u32,
+    pub project_vesting_duration: u32,
+    pub project_token_claimed: u64,
+
+    pub milestone_ratios: [u8; MAX_MILESTONES],
+    pub milestone_count: u8,
+    pub milestones_released: u8,
+    pub project_sol_claimed: u64,
+    pub cancelled: bool,
+    pub project_vesting_milestone_gated: bool,
+
+    pub vote_quorum_ratio: u8,
+    pub vote_threshold_ratio: u8,
+    pub vote_duration: u32,
+    pub proposal_count: u32,
+    pub deadline_extension: u32,
+
+    pub claim_period: u32,
+    pub sweep_policy: SweepPolicy,
+
+    pub launch_window: u32,
+    pub launch_venue: LaunchVenue,
+    pub launch_bin_step: u16,
+    pub pool_position: Option<Pubkey>,
+
+    pub lp_policy: LpPolicy,
+    pub lp_burn_ratio: u8,
+    pub lp_lock_duration: u32,
+    pub lp_fee_project_ratio: u8,
+}
+
+impl AccessControl {
+    /// The donation phase ends early once the hard cap is reached, otherwise at `donate_end`.
+    pub fn close_time(&self) -> u32 {
+        if self.closed_at != 0 {
+            self.closed_at
+        } else {
+            self.donate_end
+        }
+    }
+
+    /// Last moment the pool can be launched, after which donators may refund.
+    pub fn launch_deadline(&self) -> u32 {
+        self.close_time()
+            .saturating_add(self.launch_window)
+            .saturating_add(self.deadline_extension)
+    }
+
+    /// Donators get their SOL back when the minimum was missed or the pool was never launched in time.
+    pub fn refundable(&self, now: u32) -> bool {
+        (self.donate_amount < self.donate_amount_min && self.close_time() < now)
+            || self.launch_deadline() < now
+    }
+
+    /// Percentage of the project SOL unlocked by released milestones, all of it without milestones.
+    pub fn released_ratio(&self) -> u8 {
+        if self.milestone_count == 0 {
+            return 100;
+        }
+
+        self.milestone_ratios[..self.milestones_released as usize]
+            .iter()
+            .sum()
+    }
+
+    /// Donators can no longer claim once `claim_period` seconds passed since launch.
+    pub fn claim_expired(&self, now: u32) -> bool {
+        self.claim_period != 0
+            && self.launched_at != 0
+            && now > self.launched_at.saturating_add(self.claim_period)
+    }
+
+    /// Percentage of the launch LP burned, the rest goes to the platform.
+    pub fn lp_burn_share(&self) -> u8 {
+        match self.lp_policy {
+            LpPolicy::Platform | LpPolicy::Lock => 0,
+            LpPolicy::Burn => 100,
+            LpPolicy::Split => self.lp_burn_ratio,
+        }
+    }
+
+    /// The locked LP can be withdrawn from the LP vault from then on.
+    pub fn lp_unlock_at(&self) -> u32 {
+        self.launched_at.saturating_add(self.lp_lock_duration)
+    }
+
+    /// Project SOL locked behind milestones that were never released.
+    pub fn unreleased_sol_amount(&self) -> u64 {
+        self.sol_amount_for_project
+            - (self.sol_amount_for_project as u128 * self.released_ratio() as u128 / 100) as u64
+    }
 }
 
 #[account]
@@ -45,6 +157,27 @@ pub struct DonateTracker {
     pub donate_amount: u64,
     pub token_amount: u64,
     pub donator: Pubkey,
+
+    pub sol_amount_for_pool: u64,
+    pub sol_amount_for_project: u64,
+    pub token_amount_for_pool: u64,
+    pub token_amount_for_project: u64,
+    pub minted: u64,
+
+    pub claimed_amount: u64,
+
+    pub unreleased_refunded: bool,
+    pub expired: bool,
+    pub refunded: bool,
+
+    pub delegate: Option<Pubkey>,
+}
+
+impl DonateTracker {
+    /// Claims and refunds may be signed by the donator or its delegate.
+    pub fn is_authorized(&self, signer: &Pubkey) -> bool {
+        *signer == self.donator || self.delegate == Some(*signer)
+    }
 }
 
 #[account]
@@ -52,4 +185,414 @@ pub struct DonateTracker {
 pub struct OwnerAccount {
     pub platform_wsol: Pubkey,
     pub owner: Pubkey,
+    pub launch_window_min: u32,
+    pub launch_window_max: u32,
+}
+
+/// `OwnerAccount` layout before the launch window bounds, read by `migrate_platform`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LegacyOwnerAccount {
+    pub platform_wsol: Pubkey,
+    pub owner: Pubkey,
+}
+
+/// `AccessControl` layout before the donation features, read by `migrate_access_control`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LegacyAccessControl {
+    pub bump: u8,
+    pub token: Pubkey,
+    pub project_wallet: Pubkey,
+    pub donate_amount: u64,
+    pub donate_start: u32,
+    pub donate_end: u32,
+    pub donate_amount_min: u64,
+    pub donate_amount_max: u64,
+    pub sol_project_ratio: u8,
+    pub sol_pool_ratio: u8,
+    pub pool: Option<Pubkey>,
+    pub init_mint_rate: u64,
+    pub token_project_ratio: u8,
+    pub token_pool_ratio: u8,
+    pub token_donator_ratio: u8,
+    pub sol_amount_for_project: u64,
+    pub sol_amount_for_pool: u64,
+    pub token_amount_for_project: u64,
+    pub token_amount_for_pool: u64,
+    pub minted: u64,
+    pub wsol_account: Pubkey,
+    pub access_control_token_account: Pubkey,
+    pub donator_amount: u64,
+}
+
+impl LegacyAccessControl {
+    /// Legacy projects could be launched up to 15 days after `donate_end`, every other
+    /// new field keeps its default: no penalty, vesting, milestones, governance or claim deadline.
+    pub fn migrate(self) -> AccessControl {
+        AccessControl {
+            bump: self.bump,
+            token: self.token,
+            project_wallet: self.project_wallet,
+            donate_amount: self.donate_amount,
+            donate_start: self.donate_start,
+            donate_end: self.donate_end,
+            donate_amount_min: self.donate_amount_min,
+            donate_amount_max: self.donate_amount_max,
+            sol_project_ratio: self.sol_project_ratio,
+            sol_pool_ratio: self.sol_pool_ratio,
+            pool: self.pool,
+            init_mint_rate: self.init_mint_rate,
+            token_project_ratio: self.token_project_ratio,
+            token_pool_ratio: self.token_pool_ratio,
+            token_donator_ratio: self.token_donator_ratio,
+            sol_amount_for_project: self.sol_amount_for_project,
+            sol_amount_for_pool: self.sol_amount_for_pool,
+            token_amount_for_project: self.token_amount_for_project,
+            token_amount_for_pool: self.token_amount_for_pool,
+            minted: self.minted,
+            wsol_account: self.wsol_account,
+            access_control_token_account: self.access_control_token_account,
+            donator_amount: self.donator_amount,
+            launch_window: 15 * 24 * 60 * 60,
+            ..Default::default()
+        }
+    }
+}
+
+/// `DonateTracker` layout before the per-donation shares, read by `migrate_donate_tracker`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LegacyDonateTracker {
+    pub bump: u8,
+    pub access_control: Pubkey,
+    pub donate_amount: u64,
+    pub token_amount: u64,
+    pub donator: Pubkey,
+}
+
+impl LegacyDonateTracker {
+    /// Recomputes the tracker shares from `donate_amount` with the project ratios, the way
+    /// `donate` splits them. A share never exceeds the project aggregate, which legacy
+    /// `claim_for_project` zeroed once paid out.
+    pub fn migrate(self, access_control: &AccessControl) -> DonateTracker {
+        let minted =
+            access_control.init_mint_rate as u128 * self.donate_amount as u128 / (1e9 as u128);
+        let token_amount_for_donator = minted * access_control.token_donator_ratio as u128 / 100;
+        let token_amount_for_pool = minted * access_control.token_pool_ratio as u128 / 100;
+        let token_amount_for_project = minted - token_amount_for_pool - token_amount_for_donator;
+
+        DonateTracker {
+            bump: self.bump,
+            access_control: self.access_control,
+            donate_amount: self.donate_amount,
+            token_amount: self.token_amount,
+            donator: self.donator,
+            sol_amount_for_pool: ((self.donate_amount as u128
+                * access_control.sol_pool_ratio as u128
+                / 100) as u64)
+                .min(access_control.sol_amount_for_pool),
+            sol_amount_for_project: ((self.donate_amount as u128
+                * access_control.sol_project_ratio as u128
+                / 100) as u64)
+                .min(access_control.sol_amount_for_project),
+            token_amount_for_pool: (token_amount_for_pool as u64)
+                .min(access_control.token_amount_for_pool),
+            token_amount_for_project: (token_amount_for_project as u64)
+                .min(access_control.token_amount_for_project),
+            minted: (minted as u64).min(access_control.minted),
+            ..Default::default()
+        }
+    }
+}
+
+/// Project parameters fixed at `init_project`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProjectConfig {
+    pub donate_start: u32,
+    pub donate_end: u32,
+    pub donate_amount_min: u64,
+    pub donate_amount_max: u64,
+    pub sol_project_ratio: u8,
+    pub sol_pool_ratio: u8,
+    pub init_mint_rate: u64,
+    pub token_project_ratio: u8,
+    pub token_pool_ratio: u8,
+    pub token_donator_ratio: u8,
+    pub withdraw_penalty_ratio: u8,
+    pub withdraw_penalty_to_platform: bool,
+    pub anti_snipe_window: u32,
+    pub anti_snipe_extension: u32,
+    pub anti_snipe_extension_max: u32,
+    pub vesting_tge_ratio: u8,
+    pub vesting_cliff: u32,
+    pub vesting_duration: u32,
+    pub project_vesting_cliff: u32,
+    pub project_vesting_duration: u32,
+    pub milestone_ratios: Vec<u8>,
+    pub project_vesting_milestone_gated: bool,
+    pub vote_quorum_ratio: u8,
+    pub vote_threshold_ratio: u8,
+    pub vote_duration: u32,
+    pub claim_period: u32,
+    pub sweep_policy: SweepPolicy,
+    pub launch_window: u32,
+    pub launch_venue: LaunchVenue,
+    pub launch_bin_step: u16,
+    pub lp_policy: LpPolicy,
+    pub lp_burn_ratio: u8,
+    pub lp_lock_duration: u32,
+    /// Percentage of the harvested position fees paid to the project, Orca and DLMM only.
+    pub lp_fee_project_ratio: u8,
+}
+
+/// Where donator tokens left unclaimed after the claim deadline end up.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SweepPolicy {
+    #[default]
+    Project,
+    Platform,
+    Burn,
+}
+
+/// DEX the raised liquidity is launched into, fixed at project creation.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LaunchVenue {
+    #[default]
+    RaydiumAmmV4,
+    RaydiumCpmm,
+    OrcaWhirlpool,
+    MeteoraDlmm,
+}
+
+/// What happens to the LP minted at launch, `Split` burns `lp_burn_ratio` percent
+/// and sends the rest to the platform, `Lock` keeps it in the LP vault for `lp_lock_duration`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LpPolicy {
+    #[default]
+    Platform,
+    Burn,
+    Split,
+    Lock,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProposalKind {
+    #[default]
+    ReleaseMilestone,
+    CancelProject,
+    ExtendDeadline,
+}
+
+#[account]
+#[derive(Default)]
+pub struct Proposal {
+    pub bump: u8,
+    pub access_control: Pubkey,
+    pub proposer: Pubkey,
+    pub index: u32,
+    pub kind: ProposalKind,
+    pub extension: u32,
+    pub vote_end: u32,
+    pub yes_amount: u64,
+    pub no_amount: u64,
+    pub executed: bool,
+}
+
+#[account]
+#[derive(Default)]
+pub struct VoteRecord {
+    pub bump: u8,
+    pub proposal: Pubkey,
+    pub donator: Pubkey,
+    pub amount: u64,
+    pub approve: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn access_control() -> AccessControl {
+        AccessControl {
+            donate_end: 1_000,
+            launch_window: 100,
+            donate_amount_min: 50,
+            donate_amount: 50,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn released_ratio_without_milestones() {
+        assert_eq!(access_control().released_ratio(), 100);
+    }
+
+    #[test]
+    fn released_ratio_sums_released_milestones() {
+        let mut access_control = access_control();
+        access_control.milestone_ratios[..3].copy_from_slice(&[20, 30, 50]);
+        access_control.milestone_count = 3;
+        assert_eq!(access_control.released_ratio(), 0);
+
+        access_control.milestones_released = 2;
+        assert_eq!(access_control.released_ratio(), 50);
+
+        access_control.milestones_released = 3;
+        assert_eq!(access_control.released_ratio(), 100);
+    }
+
+    #[test]
+    fn claim_never_expires_without_period_or_launch() {
+        let mut access_control = access_control();
+        access_control.launched_at = 1_000;
+        assert!(!access_control.claim_expired(u32::MAX));
+
+        access_control.launched_at = 0;
+        access_control.claim_period = 100;
+        assert!(!access_control.claim_expired(u32::MAX));
+    }
+
+    #[test]
+    fn claim_expires_after_period() {
+        let mut access_control = access_control();
+        access_control.launched_at = 1_000;
+        access_control.claim_period = 100;
+        assert!(!access_control.claim_expired(1_100));
+        assert!(access_control.claim_expired(1_101));
+    }
+
+    #[test]
+    fn claim_expiry_saturates() {
+        let mut access_control = access_control();
+        access_control.launched_at = u32::MAX - 10;
+        access_control.claim_period = 100;
+        assert!(!access_control.claim_expired(u32::MAX));
+    }
+
+    #[test]
+    fn launch_deadline_follows_close_time() {
+        let mut access_control = access_control();
+        assert_eq!(access_control.launch_deadline(), 1_100);
+
+        access_control.closed_at = 500;
+        assert_eq!(access_control.launch_deadline(), 600);
+
+        access_control.deadline_extension = 50;
+        assert_eq!(access_control.launch_deadline(), 650);
+    }
+
+    #[test]
+    fn launch_deadline_saturates() {
+        let mut access_control = access_control();
+        access_control.donate_end = u32::MAX - 10;
+        assert_eq!(access_control.launch_deadline(), u32::MAX);
+
+        access_control.donate_end = 0;
+        access_control.launch_window = u32::MAX;
+        access_control.deadline_extension = u32::MAX;
+        assert_eq!(access_control.launch_deadline(), u32::MAX);
+    }
+
+    #[test]
+    fn refundable_when_minimum_missed() {
+        let mut access_control = access_control();
+        access_control.donate_amount = 49;
+        assert!(!access_control.refundable(1_000));
+        assert!(access_control.refundable(1_001));
+    }
+
+    #[test]
+    fn refundable_after_launch_deadline() {
+        let access_control = access_control();
+        assert!(!access_control.refundable(1_001));
+        assert!(!access_control.refundable(1_100));
+        assert!(access_control.refundable(1_101));
+    }
+
+    #[test]
+    fn never_refundable_with_saturated_deadline() {
+        let mut access_control = access_control();
+        access_control.launch_window = u32::MAX;
+        assert!(!access_control.refundable(u32::MAX));
+    }
+
+    #[test]
+    fn migrated_access_control_keeps_the_legacy_launch_timeout() {
+        let legacy = LegacyAccessControl {
+            donate_end: 1_000,
+            pool: Some(Pubkey::new_unique()),
+            sol_amount_for_pool: 70,
+            ..Default::default()
+        };
+        assert_eq!(legacy.try_to_vec().unwrap().len(), 263 - 8);
+
+        let access_control = legacy.migrate();
+        // leaves room for `pool_position`
+        assert_eq!(access_control.try_to_vec().unwrap().len(), 397 - 8 - 32);
+        assert_eq!(access_control.pool, legacy.pool);
+        assert_eq!(access_control.sol_amount_for_pool, 70);
+        assert_eq!(access_control.launch_deadline(), 1_000 + 15 * 24 * 60 * 60);
+        assert_eq!(access_control.released_ratio(), 100);
+        assert!(!access_control.claim_expired(u32::MAX));
+    }
+
+    #[test]
+    fn migrated_donate_tracker_recomputes_its_shares() {
+        let access_control = AccessControl {
+            init_mint_rate: 2_000_000_000,
+            sol_pool_ratio: 70,
+            sol_project_ratio: 30,
+            token_donator_ratio: 50,
+            token_pool_ratio: 30,
+            sol_amount_for_pool: 700,
+            sol_amount_for_project: 300,
+            token_amount_for_pool: 600,
+            token_amount_for_project: 400,
+            minted: 2_000,
+            ..Default::default()
+        };
+        let legacy = LegacyDonateTracker {
+            donate_amount: 1_000,
+            token_amount: 1_000,
+            ..Default::default()
+        };
+        assert_eq!(
+            legacy.try_to_vec().unwrap().len(),
+            8 + 1 + 32 + 8 + 8 + 32 - 8
+        );
+
+        let donate_tracker = legacy.migrate(&access_control);
+        assert_eq!(
+            donate_tracker.try_to_vec().unwrap().len(),
+            8 + 1 + 32 + 8 + 8 + 32 + 8 * 6 + 1 + 1 + 1 + 1 - 8
+        );
+        assert_eq!(donate_tracker.token_amount, 1_000);
+        assert_eq!(donate_tracker.sol_amount_for_pool, 700);
+        assert_eq!(donate_tracker.sol_amount_for_project, 300);
+        assert_eq!(donate_tracker.token_amount_for_pool, 600);
+        assert_eq!(donate_tracker.token_amount_for_project, 400);
+        assert_eq!(donate_tracker.minted, 2_000);
+        assert_eq!(donate_tracker.delegate, None);
+    }
+
+    #[test]
+    fn migrated_donate_tracker_keeps_claimed_project_shares_at_zero() {
+        let access_control = AccessControl {
+            init_mint_rate: 2_000_000_000,
+            sol_pool_ratio: 70,
+            sol_project_ratio: 30,
+            token_donator_ratio: 50,
+            token_pool_ratio: 30,
+            sol_amount_for_pool: 700,
+            token_amount_for_pool: 600,
+            minted: 2_000,
+            ..Default::default()
+        };
+        let legacy = LegacyDonateTracker {
+            donate_amount: 1_000,
+            ..Default::default()
+        };
+
+        let donate_tracker = legacy.migrate(&access_control);
+        assert_eq!(donate_tracker.sol_amount_for_project, 0);
+        assert_eq!(donate_tracker.token_amount_for_project, 0);
+    }
 }
@@ -0,0 +1,83 @@
+/// Amount of `total` unlocked at `now` for a schedule starting at `start`.
+///
+/// `tge_ratio` percent unlocks at `start`, the rest unlocks linearly over
+/// `duration` seconds once `cliff` seconds have passed. A zero `duration`
+/// unlocks everything at the end of the cliff.
+pub fn vested_amount(
+    total: u64,
+    tge_ratio: u8,
+    start: u32,
+    cliff: u32,
+    duration: u32,
+    now: u32,
+) -> u64 {
+    if now < start {
+        return 0;
+    }
+
+    let tge_amount = (total as u128 * tge_ratio as u128 / 100) as u64;
+    let elapsed = now - start;
+
+    if elapsed < cliff {
+        return tge_amount;
+    }
+
+    if duration == 0 {
+        return total;
+    }
+
+    let unlocked = (elapsed - cliff).min(duration);
+    tge_amount + ((total - tge_amount) as u128 * unlocked as u128 / duration as u128) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nothing_before_start() {
+        assert_eq!(vested_amount(1_000, 10, 100, 50, 100, 99), 0);
+    }
+
+    #[test]
+    fn tge_during_cliff() {
+        assert_eq!(vested_amount(1_000, 10, 100, 50, 100, 100), 100);
+        assert_eq!(vested_amount(1_000, 10, 100, 50, 100, 149), 100);
+    }
+
+    #[test]
+    fn linear_after_cliff() {
+        assert_eq!(vested_amount(1_000, 10, 100, 50, 100, 150), 100);
+        assert_eq!(vested_amount(1_000, 10, 100, 50, 100, 200), 550);
+        assert_eq!(vested_amount(1_000, 10, 100, 50, 100, 250), 1_000);
+        assert_eq!(vested_amount(1_000, 10, 100, 50, 100, u32::MAX), 1_000);
+    }
+
+    #[test]
+    fn zero_duration_unlocks_at_cliff_end() {
+        assert_eq!(vested_amount(1_000, 0, 100, 50, 0, 149), 0);
+        assert_eq!(vested_amount(1_000, 0, 100, 50, 0, 150), 1_000);
+    }
+
+    #[test]
+    fn full_tge_unlocks_at_start() {
+        assert_eq!(vested_amount(1_000, 100, 100, 50, 100, 100), 1_000);
+    }
+
+    #[test]
+    fn large_amounts_do_not_overflow() {
+        assert_eq!(
+            vested_amount(u64::MAX, 100, 0, u32::MAX, u32::MAX, 0),
+            u64::MAX
+        );
+        assert_eq!(
+            vested_amount(u64::MAX, 0, 0, 0, u32::MAX, u32::MAX),
+            u64::MAX
+        );
+
+        // one second short of the end leaves about `1 / duration` of the linear part locked
+        let amount = vested_amount(u64::MAX, 50, 0, 0, u32::MAX, u32::MAX - 1);
+        let locked = u64::MAX - amount;
+        assert!(locked > 0 && locked <= u64::MAX / 2 / u32::MAX as u64 + 2);
+    }
+}
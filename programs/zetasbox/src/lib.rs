@@ -1,4 +1,4 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, system_program, Discriminator};
 
 pub mod access;
 
@@ -10,34 +10,8 @@ declare_id!("AaRJMWropnNyyaTRdJUjsSvBk9WdBwpMBY1vRmwz7rE");
 pub mod zetasbox {
     use super::*;
 
-    pub fn init_project(
-        ctx: Context<InitProject>,
-        bump: u8,
-        donate_start: u32,
-        donate_end: u32,
-        donate_amount_min: u64,
-        donate_amount_max: u64,
-        sol_project_ratio: u8,
-        sol_pool_ratio: u8,
-        init_mint_rate: u64,
-        token_project_ratio: u8,
-        token_pool_ratio: u8,
-        token_donator_ratio: u8,
-    ) -> Result<()> {
-        owner::init_project(
-            ctx,
-            bump,
-            donate_start,
-            donate_end,
-            donate_amount_min,
-            donate_amount_max,
-            sol_project_ratio,
-            sol_pool_ratio,
-            init_mint_rate,
-            token_project_ratio,
-            token_pool_ratio,
-            token_donator_ratio,
-        )?;
+    pub fn init_project(ctx: Context<InitProject>, bump: u8, config: ProjectConfig) -> Result<()> {
+        owner::init_project(ctx, bump, config)?;
         Ok(())
     }
 
@@ -46,34 +20,143 @@ pub mod zetasbox {
         Ok(())
     }
 
+    pub fn withdraw_donation(ctx: Context<WithdrawDonation>, amount: u64) -> Result<()> {
+        owner::withdraw_donation(ctx, amount)?;
+        Ok(())
+    }
+
     pub fn init_donate(ctx: Context<InitDonate>) -> Result<()> {
         owner::init_donate(ctx)?;
         Ok(())
     }
 
+    pub fn set_delegate(ctx: Context<SetDelegate>, delegate: Option<Pubkey>) -> Result<()> {
+        owner::set_delegate(ctx, delegate)?;
+        Ok(())
+    }
+
     pub fn update_pool(ctx: Context<UpdatePool>, nonce: u8, open_time: u64) -> Result<()> {
         owner::update_pool(ctx, nonce, open_time)?;
         Ok(())
     }
 
+    pub fn update_pool_cpmm(ctx: Context<UpdatePoolCpmm>, open_time: u64) -> Result<()> {
+        raydium_cpmm::update_pool_cpmm(ctx, open_time)?;
+        Ok(())
+    }
+
+    pub fn update_pool_whirlpool(
+        ctx: Context<UpdatePoolWhirlpool>,
+        tick_spacing: u16,
+        whirlpool_bump: u8,
+        position_bump: u8,
+    ) -> Result<()> {
+        orca_whirlpool::update_pool_whirlpool(ctx, tick_spacing, whirlpool_bump, position_bump)?;
+        Ok(())
+    }
+
+    pub fn update_pool_dlmm(ctx: Context<UpdatePoolDlmm>) -> Result<()> {
+        meteora_dlmm::update_pool_dlmm(ctx)?;
+        Ok(())
+    }
+
+    pub fn withdraw_lp(ctx: Context<WithdrawLp>) -> Result<()> {
+        lp_vault::withdraw_lp(ctx)?;
+        Ok(())
+    }
+
+    pub fn harvest_fees_whirlpool(ctx: Context<HarvestFeesWhirlpool>) -> Result<()> {
+        lp_vault::harvest_fees_whirlpool(ctx)?;
+        Ok(())
+    }
+
+    pub fn harvest_fees_dlmm(ctx: Context<HarvestFeesDlmm>) -> Result<()> {
+        lp_vault::harvest_fees_dlmm(ctx)?;
+        Ok(())
+    }
+
+    pub fn finalize_mint(ctx: Context<FinalizeMint>) -> Result<()> {
+        claim::finalize_mint(ctx)?;
+        Ok(())
+    }
+
     pub fn claim(ctx: Context<Claim>) -> Result<()> {
         claim::claim(ctx)?;
         Ok(())
     }
 
+    pub fn distribute<'info>(ctx: Context<'_, '_, 'info, 'info, Distribute<'info>>) -> Result<()> {
+        claim::distribute(ctx)?;
+        Ok(())
+    }
+
+    pub fn sweep_unclaimed<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SweepUnclaimed<'info>>,
+    ) -> Result<()> {
+        claim::sweep_unclaimed(ctx)?;
+        Ok(())
+    }
+
     pub fn claim_for_project(ctx: Context<ClaimForProject>) -> Result<()> {
         claim::claim_for_project(ctx)?;
         Ok(())
     }
 
-    pub fn refund(ctx: Context<Refund>) -> Result<()> {
-        claim::refund(ctx)?;
+    pub fn claim_project_tokens(ctx: Context<ClaimProjectTokens>) -> Result<()> {
+        claim::claim_project_tokens(ctx)?;
+        Ok(())
+    }
+
+    pub fn refund(ctx: Context<Refund>, unwrap: bool) -> Result<()> {
+        claim::refund(ctx, unwrap)?;
+        Ok(())
+    }
+
+    pub fn approve_milestone(ctx: Context<ApproveMilestone>) -> Result<()> {
+        milestone::approve_milestone(ctx)?;
+        Ok(())
+    }
+
+    pub fn cancel_project(ctx: Context<CancelProject>) -> Result<()> {
+        milestone::cancel_project(ctx)?;
+        Ok(())
+    }
+
+    pub fn refund_all<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RefundAll<'info>>,
+        unwrap: bool,
+    ) -> Result<()> {
+        claim::refund_all(ctx, unwrap)?;
+        Ok(())
+    }
+
+    pub fn refund_unreleased(ctx: Context<RefundUnreleased>) -> Result<()> {
+        milestone::refund_unreleased(ctx)?;
+        Ok(())
+    }
+
+    pub fn create_proposal(
+        ctx: Context<CreateProposal>,
+        kind: ProposalKind,
+        extension: u32,
+    ) -> Result<()> {
+        governance::create_proposal(ctx, kind, extension)?;
+        Ok(())
+    }
+
+    pub fn vote(ctx: Context<Vote>, approve: bool) -> Result<()> {
+        governance::vote(ctx, approve)?;
+        Ok(())
+    }
+
+    pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
+        governance::execute_proposal(ctx)?;
         Ok(())
     }
 
     #[derive(Accounts)]
     pub struct InitPlatform<'info> {
-        #[account(init, payer = owner, seeds = [PLATFORM_SEED.as_ref()], space = 8 + 32 + 32, bump)]
+        #[account(init, payer = owner, seeds = [PLATFORM_SEED], space = 8 + 32 + 32 + 4 + 4, bump)]
         pub platform: Account<'info, OwnerAccount>,
         /// CHECK: test if the authority is needed
         #[account(mut)]
@@ -83,21 +166,72 @@ pub mod zetasbox {
 
     #[derive(Accounts)]
     pub struct ChangePlatform<'info> {
-        #[account(mut, has_one = owner, seeds = [PLATFORM_SEED.as_ref()], bump)]
+        #[account(mut, has_one = owner, seeds = [PLATFORM_SEED], bump)]
         pub platform: Account<'info, OwnerAccount>,
         /// CHECK: test if the auth is needed
         pub owner: Signer<'info>,
     }
 
-    pub fn init_platform(ctx: Context<InitPlatform>, platform_wsol: Pubkey) -> Result<()> {
+    #[derive(Accounts)]
+    pub struct MigratePlatform<'info> {
+        /// CHECK: Safe. Platform account sized before the launch window bounds, it does not
+        /// deserialize as `OwnerAccount` until migrated
+        #[account(mut, seeds = [PLATFORM_SEED], bump, owner = crate::ID)]
+        pub platform: UncheckedAccount<'info>,
+        #[account(mut)]
+        pub owner: Signer<'info>,
+        pub system_program: Program<'info, System>,
+    }
+
+    #[derive(Accounts)]
+    pub struct MigrateAccessControl<'info> {
+        /// CHECK: Safe. Project account sized before the donation features, it does not
+        /// deserialize as `AccessControl` until migrated
+        #[account(mut, owner = crate::ID)]
+        pub access_control: UncheckedAccount<'info>,
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        pub system_program: Program<'info, System>,
+    }
+
+    #[derive(Accounts)]
+    pub struct MigrateDonateTracker<'info> {
+        /// CHECK: Safe. Donation account sized before the per-donation shares, it does not
+        /// deserialize as `DonateTracker` until migrated
+        #[account(mut, owner = crate::ID)]
+        pub donate_tracker: UncheckedAccount<'info>,
+        pub access_control: Account<'info, AccessControl>,
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        pub system_program: Program<'info, System>,
+    }
+
+    pub fn init_platform(
+        ctx: Context<InitPlatform>,
+        platform_wsol: Pubkey,
+        launch_window_min: u32,
+        launch_window_max: u32,
+    ) -> Result<()> {
         msg!("init");
+        require!(
+            launch_window_min <= launch_window_max,
+            PlatformError::LaunchWindowError
+        );
         ctx.accounts.platform.owner = ctx.accounts.owner.key();
         ctx.accounts.platform.platform_wsol = platform_wsol;
+        ctx.accounts.platform.launch_window_min = launch_window_min;
+        ctx.accounts.platform.launch_window_max = launch_window_max;
         msg!(
             "The initial authority is {} and the initial data is {}.",
             ctx.accounts.platform.owner,
             ctx.accounts.platform.platform_wsol
         );
+        emit!(PlatformInitialized {
+            owner: ctx.accounts.platform.owner,
+            platform_wsol: ctx.accounts.platform.platform_wsol,
+            launch_window_min,
+            launch_window_max,
+        });
         Ok(())
     }
 
@@ -105,12 +239,154 @@ pub mod zetasbox {
         ctx: Context<ChangePlatform>,
         owner: Pubkey,
         platform_wsol: Pubkey,
+        launch_window_min: u32,
+        launch_window_max: u32,
     ) -> Result<()> {
         msg!("change");
+        require!(
+            launch_window_min <= launch_window_max,
+            PlatformError::LaunchWindowError
+        );
         let pda = &mut ctx.accounts.platform;
         pda.owner = owner;
         pda.platform_wsol = platform_wsol;
+        pda.launch_window_min = launch_window_min;
+        pda.launch_window_max = launch_window_max;
         msg!("new owner: {} wsol ata: {}", pda.owner, pda.platform_wsol);
+        emit!(PlatformChanged {
+            owner: pda.owner,
+            platform_wsol: pda.platform_wsol,
+            launch_window_min,
+            launch_window_max,
+        });
         Ok(())
     }
+
+    /// Grows a platform account created before the launch window bounds and sets them.
+    pub fn migrate_platform(
+        ctx: Context<MigratePlatform>,
+        launch_window_min: u32,
+        launch_window_max: u32,
+    ) -> Result<()> {
+        require!(
+            launch_window_min <= launch_window_max,
+            PlatformError::LaunchWindowError
+        );
+
+        let platform = ctx.accounts.platform.to_account_info();
+        let legacy = {
+            let data = platform.try_borrow_data()?;
+            require!(
+                data.len() == 8 + 32 + 32 && data[..8] == OwnerAccount::DISCRIMINATOR,
+                PlatformError::AlreadyMigrated
+            );
+            LegacyOwnerAccount::deserialize(&mut &data[8..])?
+        };
+
+        require!(
+            legacy.owner == ctx.accounts.owner.key(),
+            PlatformError::Unauthorized
+        );
+
+        realloc(
+            &platform,
+            &ctx.accounts.owner,
+            &ctx.accounts.system_program,
+            8 + 32 + 32 + 4 + 4,
+        )?;
+
+        let migrated = OwnerAccount {
+            platform_wsol: legacy.platform_wsol,
+            owner: legacy.owner,
+            launch_window_min,
+            launch_window_max,
+        };
+        migrated.try_serialize(&mut &mut platform.try_borrow_mut_data()?[..])?;
+
+        emit!(PlatformChanged {
+            owner: migrated.owner,
+            platform_wsol: migrated.platform_wsol,
+            launch_window_min,
+            launch_window_max,
+        });
+        Ok(())
+    }
+
+    /// Grows a project account created before the donation features, the new fields
+    /// keep the legacy behavior. Projects must be migrated before their donate trackers.
+    pub fn migrate_access_control(ctx: Context<MigrateAccessControl>) -> Result<()> {
+        let access_control = ctx.accounts.access_control.to_account_info();
+        let legacy = {
+            let data = access_control.try_borrow_data()?;
+            require!(
+                data.len() == 263 && data[..8] == AccessControl::DISCRIMINATOR,
+                PlatformError::AccountAlreadyMigrated
+            );
+            LegacyAccessControl::deserialize(&mut &data[8..])?
+        };
+
+        realloc(
+            &access_control,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            397,
+        )?;
+        legacy
+            .migrate()
+            .try_serialize(&mut &mut access_control.try_borrow_mut_data()?[..])?;
+        Ok(())
+    }
+
+    /// Grows a donate tracker created before the per-donation shares and recomputes them.
+    pub fn migrate_donate_tracker(ctx: Context<MigrateDonateTracker>) -> Result<()> {
+        let donate_tracker = ctx.accounts.donate_tracker.to_account_info();
+        let legacy = {
+            let data = donate_tracker.try_borrow_data()?;
+            require!(
+                data.len() == 8 + 1 + 32 + 8 + 8 + 32 && data[..8] == DonateTracker::DISCRIMINATOR,
+                PlatformError::AccountAlreadyMigrated
+            );
+            LegacyDonateTracker::deserialize(&mut &data[8..])?
+        };
+
+        require!(
+            legacy.access_control == ctx.accounts.access_control.key(),
+            DonateError::AccessControlAccountError
+        );
+
+        realloc(
+            &donate_tracker,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            8 + 1 + 32 + 8 + 8 + 32 + 8 * 6 + 1 + 1 + 1 + (1 + 32),
+        )?;
+        legacy
+            .migrate(&ctx.accounts.access_control)
+            .try_serialize(&mut &mut donate_tracker.try_borrow_mut_data()?[..])?;
+        Ok(())
+    }
+}
+
+/// Tops `account` up to the rent exemption of `space` from `payer` and grows it.
+fn realloc<'info>(
+    account: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    space: usize,
+) -> Result<()> {
+    let rent = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(account.lamports());
+    if rent > 0 {
+        let cpi_ctx = CpiContext::new(
+            system_program.to_account_info(),
+            system_program::Transfer {
+                from: payer.to_account_info(),
+                to: account.clone(),
+            },
+        );
+        system_program::transfer(cpi_ctx, rent)?;
+    }
+    account.realloc(space, false)?;
+    Ok(())
 }